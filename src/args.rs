@@ -0,0 +1,103 @@
+use std::{env, path::PathBuf};
+
+use anyhow::{Result, anyhow};
+
+pub enum Command {
+    Run,
+    Report { layout: String, out_path: PathBuf },
+    Compare { layout_a: String, layout_b: String },
+    Polish { layout: String, out_path: PathBuf },
+    Sweep,
+    SwapReport { swap_table: PathBuf },
+    Export { layout: String, out_path: PathBuf },
+}
+
+pub struct Args {
+    config_path: Option<PathBuf>,
+    command: Command,
+}
+
+impl Args {
+    pub fn get_config_path(&self) -> Option<&PathBuf> {
+        return self.config_path.as_ref();
+    }
+
+    pub fn get_command(&self) -> &Command {
+        return &self.command;
+    }
+}
+
+// Usage: ma_keyboard [--config <path>] [command] [command args]
+// Commands:
+// - run (default)
+// - report <layout> <out.svg|out.html>
+// - compare <layout_a> <layout_b>
+// - polish <layout> <out_layout>
+// - sweep
+// - swap-report <swap_table>
+// - export <layout> <out.c|out.kbd|out.xkb>
+// Layouts are either a built-in name (qwerty, dvorak) or a path to a saved layout file
+pub fn parse_args() -> Result<Args> {
+    let mut config_path: Option<PathBuf> = None;
+    let mut positional: Vec<String> = Vec::new();
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" | "-c" => {
+                let Some(path) = args.next() else {
+                    return Err(anyhow!("{arg} requires a path"));
+                };
+
+                config_path = Some(PathBuf::from(path));
+            }
+            _ if arg.starts_with('-') => return Err(anyhow!("Unknown argument \"{arg}\"")),
+            _ => positional.push(arg),
+        }
+    }
+
+    let command = parse_command(&positional)?;
+
+    return Ok(Args {
+        config_path,
+        command,
+    });
+}
+
+fn parse_command(positional: &[String]) -> Result<Command> {
+    let Some(name) = positional.first() else {
+        return Ok(Command::Run);
+    };
+
+    let params = &positional[1..];
+    return match (name.as_str(), params) {
+        ("run", []) => Ok(Command::Run),
+        ("report", [layout, out_path]) => Ok(Command::Report {
+            layout: layout.clone(),
+            out_path: PathBuf::from(out_path),
+        }),
+        ("report", _) => Err(anyhow!("Usage: report <layout> <out.svg|out.html>")),
+        ("compare", [layout_a, layout_b]) => Ok(Command::Compare {
+            layout_a: layout_a.clone(),
+            layout_b: layout_b.clone(),
+        }),
+        ("compare", _) => Err(anyhow!("Usage: compare <layout_a> <layout_b>")),
+        ("polish", [layout, out_path]) => Ok(Command::Polish {
+            layout: layout.clone(),
+            out_path: PathBuf::from(out_path),
+        }),
+        ("polish", _) => Err(anyhow!("Usage: polish <layout> <out_layout>")),
+        ("sweep", []) => Ok(Command::Sweep),
+        ("sweep", _) => Err(anyhow!("Usage: sweep")),
+        ("swap-report", [swap_table]) => Ok(Command::SwapReport {
+            swap_table: PathBuf::from(swap_table),
+        }),
+        ("swap-report", _) => Err(anyhow!("Usage: swap-report <swap_table>")),
+        ("export", [layout, out_path]) => Ok(Command::Export {
+            layout: layout.clone(),
+            out_path: PathBuf::from(out_path),
+        }),
+        ("export", _) => Err(anyhow!("Usage: export <layout> <out.c|out.kbd|out.xkb>")),
+        _ => Err(anyhow!("Unknown command \"{name}\"")),
+    };
+}
@@ -0,0 +1,310 @@
+extern crate alloc;
+
+use {alloc::collections::BTreeMap, core::cmp};
+
+use crate::{
+    corpus::{QWERTY_PCT, get_corpus_groups, get_corpus_sets},
+    eval_funcs::{BigramKind, classify_bigram, get_key_eff, get_layer_mult},
+    keyboard::{Finger, Hand, Keyboard},
+    layout_file::key_label,
+    structs::{Key, Slot},
+};
+
+const LIST_LEN: usize = 10;
+
+// Efficiency totals before the hand balance multiplier is applied. Keys are typed with no
+// history when the previous character is not on the keyboard
+struct Breakdown {
+    metrics: BTreeMap<Option<BigramKind>, (usize, f64)>,
+    bigrams: BTreeMap<(char, char), f64>,
+    left: usize,
+    right: usize,
+}
+
+impl Breakdown {
+    fn from_keyboard(kb: &Keyboard) -> Self {
+        let mut breakdown = Self {
+            metrics: BTreeMap::new(),
+            bigrams: BTreeMap::new(),
+            left: 0,
+            right: 0,
+        };
+
+        // Scored on physical slots, as in Keyboard::eval, with history reset for each corpus.
+        // last_key keeps the layer slot so the bigram can be named
+        for entries in get_corpus_groups() {
+            let mut last_slot: Option<Slot> = None;
+            let mut prev_slot: Option<Slot> = None;
+            let mut last_key: Option<Slot> = None;
+            let mut last_layer: usize = 0;
+
+            for entry in entries {
+                for c in entry.chars() {
+                    let Some(key_slot) = kb.get_char_slot(c) else {
+                        prev_slot = last_slot;
+                        last_slot = None;
+                        last_key = None;
+                        last_layer = 0;
+                        continue;
+                    };
+
+                    let this_slot = key_slot.get_phys();
+                    let eff = get_key_eff(this_slot, last_slot, prev_slot)
+                        * get_layer_mult(key_slot.get_layer(), last_layer);
+                    let kind = last_slot.map(|last| return classify_bigram(this_slot, last));
+                    let metric = breakdown.metrics.entry(kind).or_insert((0, 0.0));
+                    metric.0 += 1;
+                    metric.1 += eff;
+
+                    if let Some(last) = last_key
+                        && let (Some(last_base), Some(this_base)) =
+                            (kb.get_slot_key(last), kb.get_slot_key(key_slot))
+                    {
+                        let bigram = (last_base.get_base(), this_base.get_base());
+                        *breakdown.bigrams.entry(bigram).or_insert(0.0) += eff;
+                    }
+
+                    // Thumbs are left out of the hand balance, as in Keyboard::eval
+                    if Finger::from_slot(this_slot) != Finger::Thumb {
+                        if Hand::from_slot(this_slot) == Hand::Left {
+                            breakdown.left += 1;
+                        } else {
+                            breakdown.right += 1;
+                        }
+                    }
+
+                    prev_slot = last_slot;
+                    last_slot = Some(this_slot);
+                    last_key = Some(key_slot);
+                    last_layer = key_slot.get_layer();
+                }
+            }
+        }
+
+        return breakdown;
+    }
+
+    fn get_balance_mult(&self) -> f64 {
+        let (low, high) = if self.left < self.right {
+            (self.left, self.right)
+        } else {
+            (self.right, self.left)
+        };
+
+        if high == 0 {
+            return 0.0;
+        }
+
+        return low as f64 / high as f64;
+    }
+}
+
+pub fn compare_layouts(name_a: &str, kb_a: &mut Keyboard, name_b: &str, kb_b: &mut Keyboard) {
+    kb_a.eval();
+    kb_b.eval();
+
+    let breakdown_a = Breakdown::from_keyboard(kb_a);
+    let breakdown_b = Breakdown::from_keyboard(kb_b);
+
+    println!();
+    println!("== Scores ==");
+    println!("{name_a}: {:.4}", kb_a.get_score());
+    println!("{name_b}: {:.4}", kb_b.get_score());
+    if kb_a.get_score() > 0.0 {
+        println!(
+            "Ratio ({name_b} / {name_a}): {:.4}",
+            kb_b.get_score() / kb_a.get_score()
+        );
+    }
+
+    print_corpus_scores(name_a, kb_a, name_b, kb_b);
+    print_moved_keys(kb_a, kb_b);
+    print_metrics(&breakdown_a, &breakdown_b);
+    print_bigram_changes(&breakdown_a, &breakdown_b);
+    println!();
+}
+
+// Each corpus of corpus.sets as a percentage of Qwerty's score on it
+fn print_corpus_scores(name_a: &str, kb_a: &mut Keyboard, name_b: &str, kb_b: &mut Keyboard) {
+    let sets = get_corpus_sets();
+    if sets.is_empty() {
+        return;
+    }
+
+    let scores_a = kb_a.get_corpus_scores();
+    let scores_b = kb_b.get_corpus_scores();
+
+    println!();
+    println!("== Corpus Scores ==");
+    // Columns are widened to fit the layout names, which are often file paths
+    let width_a = (name_a.chars().count() + 2).max(10);
+    let width_b = (name_b.chars().count() + 2).max(10);
+    println!(
+        "{:<12}{name_a:>width_a$}{name_b:>width_b$}{:>10}",
+        "Corpus", "Delta"
+    );
+    for ((set, score_a), score_b) in sets.iter().zip(scores_a).zip(scores_b) {
+        let spec = set.get_spec();
+        let floor = spec.get_floor().map_or(String::new(), |f| {
+            return format!(" (floor {:.2})", f * QWERTY_PCT);
+        });
+        println!(
+            "{:<12}{score_a:>width_a$.2}{score_b:>width_b$.2}{:>+10.2}{floor}",
+            spec.get_name(),
+            score_b - score_a
+        );
+    }
+}
+
+fn print_moved_keys(kb_a: &Keyboard, kb_b: &Keyboard) {
+    let slots_b: BTreeMap<char, Slot> = kb_b
+        .get_key_slots()
+        .iter()
+        .map(|(slot, key)| return (key.get_base(), *slot))
+        .collect();
+
+    println!();
+    println!("== Moved Keys ==");
+
+    let mut moved: usize = 0;
+    for (slot_a, key) in kb_a.get_key_slots() {
+        if *key == Key::BLANK {
+            continue;
+        }
+
+        let Some(slot_b) = slots_b.get(&key.get_base()) else {
+            continue;
+        };
+
+        if slot_a == slot_b {
+            continue;
+        }
+
+        moved += 1;
+        println!(
+            "{:>5}: {} -> {}",
+            key_label(key.get_base()),
+            describe_slot(*slot_a),
+            describe_slot(*slot_b)
+        );
+    }
+
+    println!("{moved} keys moved");
+}
+
+fn print_metrics(breakdown_a: &Breakdown, breakdown_b: &Breakdown) {
+    println!();
+    println!("== Efficiency by Bigram Type ==");
+    println!(
+        "{:<24}{:>12}{:>12}{:>14}{:>14}{:>14}",
+        "Type", "Count A", "Count B", "Eff. A", "Eff. B", "Delta"
+    );
+
+    let mut kinds: Vec<Option<BigramKind>> = breakdown_a
+        .metrics
+        .keys()
+        .chain(breakdown_b.metrics.keys())
+        .copied()
+        .collect();
+    kinds.sort();
+    kinds.dedup();
+
+    for kind in kinds {
+        let (cnt_a, eff_a) = breakdown_a.metrics.get(&kind).copied().unwrap_or((0, 0.0));
+        let (cnt_b, eff_b) = breakdown_b.metrics.get(&kind).copied().unwrap_or((0, 0.0));
+        let name = kind.map_or("No history", BigramKind::get_name);
+
+        println!(
+            "{:<24}{:>12}{:>12}{:>14.2}{:>14.2}{:>+14.2}",
+            name,
+            cnt_a,
+            cnt_b,
+            eff_a,
+            eff_b,
+            eff_b - eff_a
+        );
+    }
+
+    let balance_a = breakdown_a.get_balance_mult();
+    let balance_b = breakdown_b.get_balance_mult();
+    println!(
+        "{:<24}{:>12}{:>12}{:>14.4}{:>14.4}{:>+14.4}",
+        "Hand balance mult.",
+        "",
+        "",
+        balance_a,
+        balance_b,
+        balance_b - balance_a
+    );
+}
+
+// Bigrams are keyed by the unshifted characters of the keys involved, so both layouts must use
+// the same key set
+fn print_bigram_changes(breakdown_a: &Breakdown, breakdown_b: &Breakdown) {
+    let mut deltas: Vec<((char, char), f64)> = breakdown_a
+        .bigrams
+        .iter()
+        .map(|(bigram, eff_a)| {
+            let eff_b = breakdown_b.bigrams.get(bigram).copied().unwrap_or(0.0);
+            return (*bigram, eff_b - eff_a);
+        })
+        .collect();
+
+    deltas.sort_by(|a, b| return b.1.partial_cmp(&a.1).unwrap_or(cmp::Ordering::Equal));
+
+    println!();
+    println!("== Most Improved Bigrams ==");
+    for (bigram, delta) in deltas.iter().take(LIST_LEN).filter(|d| return d.1 > 0.0) {
+        print_bigram(*bigram, *delta);
+    }
+
+    println!();
+    println!("== Most Worsened Bigrams ==");
+    for (bigram, delta) in deltas
+        .iter()
+        .rev()
+        .take(LIST_LEN)
+        .filter(|d| return d.1 < 0.0)
+    {
+        print_bigram(*bigram, *delta);
+    }
+}
+
+fn print_bigram(bigram: (char, char), delta: f64) {
+    let label = format!("{} {}", key_label(bigram.0), key_label(bigram.1));
+    println!("{label:>12}{delta:>+14.2}");
+}
+
+fn describe_slot(key_slot: Slot) -> String {
+    let slot = key_slot.get_phys();
+    let layer = match key_slot.get_layer() {
+        0 => String::new(),
+        n => format!("layer {n} "),
+    };
+
+    let row = match slot.get_row() {
+        0 => "number",
+        1 => "top",
+        2 => "home",
+        3 => "bottom",
+        _ => "thumb",
+    };
+
+    let hand = match Hand::from_slot(slot) {
+        Hand::Left => "left",
+        Hand::Right => "right",
+    };
+
+    let finger = match Finger::from_slot(slot) {
+        Finger::Pinky => "pinky",
+        Finger::Ring => "ring",
+        Finger::Middle => "middle",
+        Finger::Index => "index",
+        Finger::Thumb => "thumb",
+    };
+
+    return format!(
+        "{layer}{row} row, col {:02} ({hand} {finger})",
+        slot.get_col()
+    );
+}
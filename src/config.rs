@@ -0,0 +1,1536 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::OnceLock,
+    time::Duration,
+};
+
+use anyhow::{Result, anyhow};
+
+use crate::{
+    corpus::{CorpusObjective, CorpusSpec},
+    crossover::CrossoverOp,
+    eval_funcs::StaggerModel,
+    keyboard::{
+        Board, CharsetKey, DeadKeySeq, ErgoMod, FINGER_CNT, Finger, FingerId, FingerOverride,
+        IsoKey, MAX_LAYERS, ThumbKey, ThumbPos, xkb_dead_keysym,
+    },
+    keys,
+    layout_file::escape_key,
+    local_search::LocalSearchMethod,
+    mapped_swap::{Confidence, SwapPolicy},
+    migration::{MigrantChoice, Topology},
+    selection::SelectionStrategy,
+    static_keys, swappable_keys,
+    sweep::SweepMode,
+    variation::{Perturbation, Recombination},
+};
+
+swappable_keys!();
+static_keys!();
+
+// Keys placed on the main rows besides the swappable ones
+const STATIC_KEYS: [(char, char); 17] = [
+    NEWLINE, BACKSLASH, ONE, TWO, THREE, FOUR, FIVE, SIX, SEVEN, EIGHT, NINE, ZERO, L_BRACKET,
+    R_BRACKET, DASH, EQUALS, F_SLASH,
+];
+
+pub static CONFIG: OnceLock<Config> = OnceLock::new();
+
+// fixed_softmax scores are scaled to 0-1, so a lower temperature already picks the best key
+// almost every time
+const MIN_SWAP_TEMP: f64 = 0.01;
+// One offset per finger column, left pinky through right pinky
+const COLUMN_OFFSET_CNT: usize = 10;
+// Every column of the main rows, through the pipe column
+const FINGER_COLUMN_CNT: usize = 13;
+// From the left pinky to the right pinky, counting both thumbs. Weaker fingers travel less far
+// in the distance-based score, and the rule-based score holds these as the healthy baseline
+pub const DEFAULT_STRENGTH: [f64; FINGER_CNT] = [0.5, 0.7, 1.0, 1.0, 0.8, 0.8, 1.0, 1.0, 0.7, 0.5];
+
+// NOTE: Options are read from a plain "key = value" file. Blank lines and lines starting with #
+// are ignored. Any option not present in the file keeps its default. Optional settings can be
+// turned off by setting them to "none"
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub stop: StopConfig,
+    pub diversity: DiversityConfig,
+    pub crossover: CrossoverConfig,
+    pub selection: SelectionConfig,
+    pub local_search: LocalSearchConfig,
+    pub swap_policy: SwapPolicyConfig,
+    pub polish: PolishConfig,
+    pub migration: MigrationConfig,
+    pub meta: MetaConfig,
+    pub population: PopulationConfig,
+    pub sweep: SweepConfig,
+    pub variation: VariationConfig,
+    pub swap_table: SwapTableConfig,
+    pub geometry: GeometryConfig,
+    pub thumb: ThumbConfig,
+    pub layer: LayerConfig,
+    pub effort: EffortConfig,
+    pub fingers: FingerConfig,
+    pub charset: CharsetConfig,
+    pub dead_keys: DeadKeyConfig,
+    pub corpus: CorpusConfig,
+}
+
+impl Config {
+    pub fn new() -> Self {
+        return Self {
+            stop: StopConfig::new(),
+            diversity: DiversityConfig::new(),
+            crossover: CrossoverConfig::new(),
+            selection: SelectionConfig::new(),
+            local_search: LocalSearchConfig::new(),
+            swap_policy: SwapPolicyConfig::new(),
+            polish: PolishConfig::new(),
+            migration: MigrationConfig::new(),
+            meta: MetaConfig::new(),
+            population: PopulationConfig::new(),
+            sweep: SweepConfig::new(),
+            variation: VariationConfig::new(),
+            swap_table: SwapTableConfig::new(),
+            geometry: GeometryConfig::new(),
+            thumb: ThumbConfig::new(),
+            layer: LayerConfig::new(),
+            effort: EffortConfig::new(),
+            fingers: FingerConfig::new(),
+            charset: CharsetConfig::new(),
+            dead_keys: DeadKeyConfig::new(),
+            corpus: CorpusConfig::new(),
+        };
+    }
+
+    fn apply_entry(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "stop.max_generations" => self.stop.max_generations = parse_opt(key, value)?,
+            "stop.max_time_secs" => {
+                let secs: Option<u64> = parse_opt(key, value)?;
+                self.stop.max_time = secs.map(Duration::from_secs);
+            }
+            "stop.max_evals" => self.stop.max_evals = parse_opt(key, value)?,
+            "stop.target_score" => self.stop.target_score = parse_opt(key, value)?,
+            "stop.stagnation_gens" => self.stop.stagnation_gens = parse_opt(key, value)?,
+            "stop.min_diversity" => self.stop.min_diversity = parse_opt(key, value)?,
+            "diversity.sharing_radius" => {
+                self.diversity.sharing_radius = parse_opt(key, value)?;
+            }
+            "diversity.sharing_alpha" => self.diversity.sharing_alpha = parse_value(key, value)?,
+            "crossover.op" => self.crossover.op = parse_opt(key, value)?,
+            "crossover.rate" => self.crossover.rate = parse_value(key, value)?,
+            "selection.population" => {
+                self.selection.population = if value.eq_ignore_ascii_case("evolve") {
+                    None
+                } else {
+                    Some(parse_value(key, value)?)
+                };
+            }
+            "selection.meta" => self.selection.meta = parse_value(key, value)?,
+            "selection.cull_pct" => self.selection.cull_pct = parse_value(key, value)?,
+            "local_search.method" => {
+                self.local_search.method = if value.eq_ignore_ascii_case("evolve") {
+                    None
+                } else {
+                    Some(parse_value(key, value)?)
+                };
+            }
+            "local_search.anneal_steps" => {
+                self.local_search.anneal_steps = parse_value(key, value)?;
+            }
+            "local_search.anneal_temp" => self.local_search.anneal_temp = parse_value(key, value)?,
+            "local_search.tabu_tenure" => self.local_search.tabu_tenure = parse_value(key, value)?,
+            "local_search.tabu_sample" => self.local_search.tabu_sample = parse_value(key, value)?,
+            "local_search.tabu_stale_steps" => {
+                self.local_search.tabu_stale_steps = parse_value(key, value)?;
+            }
+            "swap_policy.method" => {
+                self.swap_policy.method = if value.eq_ignore_ascii_case("evolve") {
+                    None
+                } else {
+                    Some(parse_value(key, value)?)
+                };
+            }
+            "swap_policy.temp" => self.swap_policy.temp = parse_value(key, value)?,
+            "swap_policy.epsilon" => self.swap_policy.epsilon = parse_value(key, value)?,
+            "swap_policy.top_k" => self.swap_policy.top_k = parse_value(key, value)?,
+            "polish.after_run" => self.polish.after_run = parse_value(key, value)?,
+            "polish.cycles" => self.polish.cycles = parse_value(key, value)?,
+            "migration.topology" => self.migration.topology = parse_opt(key, value)?,
+            "migration.interval" => self.migration.interval = parse_value(key, value)?,
+            "migration.count" => self.migration.count = parse_value(key, value)?,
+            "migration.choice" => self.migration.choice = parse_value(key, value)?,
+            _ => return self.apply_run_entry(key, value),
+        }
+
+        return Ok(());
+    }
+
+    // Settings for the shape of a run: the meta-population, the population bounds, and sweeps
+    fn apply_run_entry(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "meta.evolve" => self.meta.evolve = parse_value(key, value)?,
+            "meta.pop_size" => self.meta.pop_size = parse_value(key, value)?,
+            "meta.to_remove" => self.meta.to_remove = parse_value(key, value)?,
+            "meta.elite_cnt" => self.meta.elite_cnt = parse_value(key, value)?,
+            "population.min_pop" => self.population.min_pop = parse_value(key, value)?,
+            "population.max_pop" => self.population.max_pop = parse_value(key, value)?,
+            "population.min_climb_pct" => {
+                self.population.min_climb_pct = parse_value(key, value)?;
+            }
+            "population.max_climb_pct" => {
+                self.population.max_climb_pct = parse_value(key, value)?;
+            }
+            "population.elite_cnt" => self.population.elite_cnt = parse_value(key, value)?,
+            "population.min_mutation" => self.population.min_mutation = parse_value(key, value)?,
+            "population.max_mutation" => self.population.max_mutation = parse_value(key, value)?,
+            "population.min_score_decay" => {
+                self.population.min_score_decay = parse_value(key, value)?;
+            }
+            "population.max_score_decay" => {
+                self.population.max_score_decay = parse_value(key, value)?;
+            }
+            "population.min_k_temp" => self.population.min_k_temp = parse_value(key, value)?,
+            "population.max_k_temp" => self.population.max_k_temp = parse_value(key, value)?,
+            "population.mutation_rate" => {
+                self.population.mutation_rate = parse_value(key, value)?;
+            }
+            "variation.recombination" => {
+                self.variation.recombination = parse_value(key, value)?;
+            }
+            "variation.perturbation" => self.variation.perturbation = parse_value(key, value)?,
+            "variation.blx_alpha" => self.variation.blx_alpha = parse_value(key, value)?,
+            "variation.sbx_eta" => self.variation.sbx_eta = parse_value(key, value)?,
+            "variation.initial_sigma" => self.variation.initial_sigma = parse_value(key, value)?,
+            "variation.log_k_temp" => self.variation.log_k_temp = parse_value(key, value)?,
+            "swap_table.load" => self.swap_table.load = parse_opt(key, value)?,
+            "swap_table.load_decay" => self.swap_table.load_decay = parse_value(key, value)?,
+            "swap_table.save" => self.swap_table.save = parse_value(key, value)?,
+            "swap_table.confidence" => self.swap_table.confidence = parse_value(key, value)?,
+            "swap_table.exploration" => self.swap_table.exploration = parse_value(key, value)?,
+            "sweep.mode" => self.sweep.mode = parse_value(key, value)?,
+            "sweep.grid_steps" => self.sweep.grid_steps = parse_value(key, value)?,
+            "sweep.samples" => self.sweep.samples = parse_value(key, value)?,
+            "sweep.repeats" => self.sweep.repeats = parse_value(key, value)?,
+            "sweep.generations" => self.sweep.generations = parse_value(key, value)?,
+            "sweep.target_score" => self.sweep.target_score = parse_opt(key, value)?,
+            _ => return self.apply_board_entry(key, value),
+        }
+
+        return Ok(());
+    }
+
+    // Settings describing the physical keyboard being scored
+    fn apply_board_entry(&mut self, key: &str, value: &str) -> Result<()> {
+        match key {
+            "geometry.stagger" => self.geometry.stagger = parse_value(key, value)?,
+            "geometry.column_offsets" => {
+                self.geometry.column_offsets = parse_list(key, value)?;
+            }
+            "geometry.board" => self.geometry.board = parse_value(key, value)?,
+            "geometry.iso_key" => self.geometry.iso_key = parse_value(key, value)?,
+            "thumb.keys" => {
+                self.thumb.keys = if value.eq_ignore_ascii_case("none") {
+                    Vec::new()
+                } else {
+                    parse_list(key, value)?
+                };
+            }
+            "layer.count" => self.layer.count = parse_value(key, value)?,
+            "layer.access" => self.layer.access = parse_list(key, value)?,
+            "layer.switch_cost" => self.layer.switch_cost = parse_value(key, value)?,
+            "layer.hold_cost" => self.layer.hold_cost = parse_value(key, value)?,
+            "effort.distance_weight" => self.effort.distance_weight = parse_value(key, value)?,
+            "effort.distance_scale" => self.effort.distance_scale = parse_value(key, value)?,
+            "effort.lateral_weight" => self.effort.lateral_weight = parse_value(key, value)?,
+            "fingers.columns" => self.fingers.columns = parse_list(key, value)?,
+            "fingers.mods" => {
+                self.fingers.mods = if value.eq_ignore_ascii_case("none") {
+                    Vec::new()
+                } else {
+                    parse_list(key, value)?
+                };
+            }
+            "fingers.overrides" => {
+                self.fingers.overrides = if value.eq_ignore_ascii_case("none") {
+                    Vec::new()
+                } else {
+                    parse_list(key, value)?
+                };
+            }
+            "fingers.strength" => self.fingers.strength = parse_list(key, value)?,
+            "fingers.load_targets" => {
+                self.fingers.load_targets = if value.eq_ignore_ascii_case("none") {
+                    None
+                } else {
+                    Some(parse_list(key, value)?)
+                };
+            }
+            "fingers.load_weight" => self.fingers.load_weight = parse_value(key, value)?,
+            "charset.keys" => {
+                self.charset.keys = if value.eq_ignore_ascii_case("none") {
+                    Vec::new()
+                } else {
+                    parse_list(key, value)?
+                };
+            }
+            "dead_keys.sequences" => {
+                self.dead_keys.sequences = if value.eq_ignore_ascii_case("none") {
+                    Vec::new()
+                } else {
+                    parse_list(key, value)?
+                };
+            }
+            "corpus.sets" => {
+                self.corpus.sets = if value.eq_ignore_ascii_case("none") {
+                    Vec::new()
+                } else {
+                    parse_list(key, value)?
+                };
+            }
+            "corpus.objective" => self.corpus.objective = parse_value(key, value)?,
+            _ => return Err(anyhow!("Unknown config key \"{key}\"")),
+        }
+
+        return Ok(());
+    }
+
+    fn validate(&self) -> Result<()> {
+        self.stop.validate()?;
+        self.diversity.validate()?;
+        self.crossover.validate()?;
+        self.selection.validate()?;
+        self.local_search.validate()?;
+        self.swap_policy.validate()?;
+        self.migration.validate()?;
+        self.meta.validate()?;
+        self.population.validate()?;
+        self.sweep.validate()?;
+        self.variation.validate()?;
+        self.swap_table.validate()?;
+        self.geometry.validate()?;
+        self.thumb.validate()?;
+        self.layer.validate()?;
+        self.effort.validate()?;
+        self.fingers.validate()?;
+        self.charset.validate()?;
+        self.dead_keys.validate()?;
+        self.corpus.validate()?;
+
+        if self.geometry.board == Board::Iso {
+            if self.layer.count > 0 {
+                return Err(anyhow!(
+                    "geometry.board = iso cannot be combined with layers, which model a small \
+                     split board"
+                ));
+            }
+
+            if let IsoKey::Static(key) = self.geometry.iso_key
+                && self.thumb.keys.iter().any(|t| {
+                    return t.get_key() == key.get_base() || t.get_key() == key.get_shift();
+                })
+            {
+                return Err(anyhow!(
+                    "geometry.iso_key {} is already a thumb key",
+                    self.geometry.iso_key
+                ));
+            }
+        }
+
+        if self.variation.log_k_temp
+            && self.population.min_k_temp * self.population.max_k_temp <= 0.0_f64
+        {
+            return Err(anyhow!(
+                "variation.log_k_temp needs population.min_k_temp and population.max_k_temp to \
+                 share a sign, found {} and {}",
+                self.population.min_k_temp,
+                self.population.max_k_temp
+            ));
+        }
+
+        // Keys from charset.keys are placed by the board like any other key
+        let mut placed: Vec<char> = self.thumb.keys.iter().map(|t| return t.get_key()).collect();
+        if let IsoKey::Static(iso_key) = self.geometry.iso_key {
+            placed.extend([iso_key.get_base(), iso_key.get_shift()]);
+        }
+
+        for charset_key in &self.charset.keys {
+            let key = charset_key.get_key();
+            if placed.contains(&key.get_base()) || placed.contains(&key.get_shift()) {
+                return Err(anyhow!(
+                    "charset.keys {charset_key} is already a thumb key or the ISO key"
+                ));
+            }
+        }
+
+        // Dead keys and the keys after them are pressed, so the board has to type them directly
+        let typed = self.get_typed_chars();
+        for seq in &self.dead_keys.sequences {
+            if typed.contains(&seq.get_composed()) {
+                return Err(anyhow!(
+                    "dead_keys.sequences {seq} composes a character the board already types"
+                ));
+            }
+
+            if seq.get_dead() == NEWLINE.0 {
+                return Err(anyhow!(
+                    "dead_keys.sequences {seq} cannot use Enter as a dead key"
+                ));
+            }
+
+            if !typed.contains(&seq.get_dead()) || !typed.contains(&seq.get_base()) {
+                return Err(anyhow!(
+                    "dead_keys.sequences {seq} uses a key the board does not type. Add it with \
+                     charset.keys, thumb.keys, or geometry.iso_key"
+                ));
+            }
+        }
+
+        let access = self.layer.access.iter().take(self.layer.count);
+        for pos in access {
+            if self.thumb.keys.iter().any(|t| return t.get_pos() == *pos) {
+                return Err(anyhow!(
+                    "layer.access uses thumb position {pos}, which thumb.keys already holds"
+                ));
+            }
+        }
+
+        if !self.meta.evolve && self.stop.min_diversity.is_some() {
+            return Err(anyhow!(
+                "stop.min_diversity needs more than one population. Remove it or set meta.evolve"
+            ));
+        }
+
+        return Ok(());
+    }
+
+    // Every character a key of the board sends, after charset.keys replacements
+    pub fn get_typed_chars(&self) -> Vec<char> {
+        let mut typed: Vec<char> = Vec::new();
+        for (base, shift) in STATIC_KEYS.iter().chain(SWAPPABLE_KEYS.iter()) {
+            if let Some(charset_key) = self
+                .charset
+                .keys
+                .iter()
+                .find(|k| return k.get_old() == *base)
+            {
+                let key = charset_key.get_key();
+                typed.extend([key.get_base(), key.get_shift()]);
+            } else {
+                typed.extend([*base, *shift]);
+            }
+        }
+
+        typed.extend(self.thumb.keys.iter().map(|t| return t.get_key()));
+        if let IsoKey::Static(iso_key) = self.geometry.iso_key {
+            typed.extend([iso_key.get_base(), iso_key.get_shift()]);
+        }
+
+        return typed;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct StopConfig {
+    pub max_generations: Option<usize>,
+    pub max_time: Option<Duration>,
+    pub max_evals: Option<usize>,
+    pub target_score: Option<f64>,
+    pub stagnation_gens: Option<usize>,
+    pub min_diversity: Option<f64>,
+}
+
+impl StopConfig {
+    pub fn new() -> Self {
+        return Self {
+            max_generations: Some(2000),
+            max_time: None,
+            max_evals: None,
+            target_score: None,
+            stagnation_gens: None,
+            min_diversity: None,
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        let any_set = self.max_generations.is_some()
+            || self.max_time.is_some()
+            || self.max_evals.is_some()
+            || self.target_score.is_some()
+            || self.stagnation_gens.is_some()
+            || self.min_diversity.is_some();
+        if !any_set {
+            return Err(anyhow!("At least one stop.* criterion must be set"));
+        }
+
+        if self.max_generations == Some(0) || self.stagnation_gens == Some(0) {
+            return Err(anyhow!(
+                "Generation based stop criteria must be greater than zero"
+            ));
+        }
+
+        if let Some(min_diversity) = self.min_diversity
+            && min_diversity < 0.0_f64
+        {
+            return Err(anyhow!(
+                "stop.min_diversity ({min_diversity}) cannot be negative"
+            ));
+        }
+
+        return Ok(());
+    }
+}
+
+// Fitness sharing is off unless a radius is set. The radius is measured in differing slots
+#[derive(Debug, Clone)]
+pub struct DiversityConfig {
+    pub sharing_radius: Option<f64>,
+    pub sharing_alpha: f64,
+}
+
+impl DiversityConfig {
+    pub fn new() -> Self {
+        return Self {
+            sharing_radius: None,
+            sharing_alpha: 1.0,
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        if let Some(radius) = self.sharing_radius
+            && radius <= 0.0_f64
+        {
+            return Err(anyhow!(
+                "diversity.sharing_radius ({radius}) must be greater than zero"
+            ));
+        }
+
+        if self.sharing_alpha <= 0.0_f64 {
+            return Err(anyhow!(
+                "diversity.sharing_alpha ({}) must be greater than zero",
+                self.sharing_alpha
+            ));
+        }
+
+        return Ok(());
+    }
+}
+
+// When an operator is set, this share of each population's new keyboards are bred from two of
+// its climbers. The rest are still built from the swap table
+#[derive(Debug, Clone)]
+pub struct CrossoverConfig {
+    pub op: Option<CrossoverOp>,
+    pub rate: f64,
+}
+
+impl CrossoverConfig {
+    pub fn new() -> Self {
+        return Self {
+            op: None,
+            rate: 0.5,
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !(0.0_f64..=1.0_f64).contains(&self.rate) {
+            return Err(anyhow!(
+                "crossover.rate ({}) must be between 0 and 1",
+                self.rate
+            ));
+        }
+
+        return Ok(());
+    }
+}
+
+// A population strategy of None means each population carries its own strategy, which is
+// inherited and mutated like the other population hyperparameters
+#[derive(Debug, Clone)]
+pub struct SelectionConfig {
+    pub population: Option<SelectionStrategy>,
+    pub meta: SelectionStrategy,
+    pub cull_pct: f64,
+}
+
+impl SelectionConfig {
+    pub fn new() -> Self {
+        return Self {
+            population: Some(SelectionStrategy::Roulette),
+            meta: SelectionStrategy::Roulette,
+            cull_pct: 0.0,
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !(0.0_f64..1.0_f64).contains(&self.cull_pct) {
+            return Err(anyhow!(
+                "selection.cull_pct ({}) must be at least 0 and less than 1",
+                self.cull_pct
+            ));
+        }
+
+        return Ok(());
+    }
+}
+
+// A method of None means each population carries its own method, which is inherited and mutated
+// like the other population hyperparameters. The annealing temperature is relative to the current
+// score, so 0.01 means a swap losing 1% of the score is accepted about a third of the time at the
+// start of the climb
+#[derive(Debug, Clone)]
+pub struct LocalSearchConfig {
+    pub method: Option<LocalSearchMethod>,
+    pub anneal_steps: usize,
+    pub anneal_temp: f64,
+    pub tabu_tenure: usize,
+    pub tabu_sample: usize,
+    pub tabu_stale_steps: usize,
+}
+
+impl LocalSearchConfig {
+    pub fn new() -> Self {
+        return Self {
+            method: Some(LocalSearchMethod::Plateau),
+            anneal_steps: 500,
+            anneal_temp: 0.01,
+            tabu_tenure: 10,
+            tabu_sample: 8,
+            tabu_stale_steps: 20,
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.anneal_steps == 0 || self.tabu_sample == 0 || self.tabu_stale_steps == 0 {
+            return Err(anyhow!(
+                "local_search step and sample counts must be greater than zero"
+            ));
+        }
+
+        if self.anneal_temp < 0.0_f64 {
+            return Err(anyhow!(
+                "local_search.anneal_temp ({}) cannot be negative",
+                self.anneal_temp
+            ));
+        }
+
+        return Ok(());
+    }
+}
+
+// How keys are picked from the swap table when building and mutating keyboards. None means each
+// population carries its own policy as an evolving gene
+#[derive(Debug, Clone)]
+pub struct SwapPolicyConfig {
+    pub method: Option<SwapPolicy>,
+    pub temp: f64,
+    pub epsilon: f64,
+    pub top_k: usize,
+}
+
+impl SwapPolicyConfig {
+    pub fn new() -> Self {
+        return Self {
+            method: Some(SwapPolicy::VarianceSoftmax),
+            temp: 0.05,
+            epsilon: 0.1,
+            top_k: 3,
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !self.temp.is_finite() || self.temp < MIN_SWAP_TEMP {
+            return Err(anyhow!(
+                "swap_policy.temp ({}) must be at least {MIN_SWAP_TEMP}",
+                self.temp
+            ));
+        }
+
+        if !(0.0_f64..=1.0_f64).contains(&self.epsilon) {
+            return Err(anyhow!(
+                "swap_policy.epsilon ({}) must be between 0 and 1",
+                self.epsilon
+            ));
+        }
+
+        if self.top_k == 0 {
+            return Err(anyhow!("swap_policy.top_k must be at least 1"));
+        }
+
+        return Ok(());
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PolishConfig {
+    pub after_run: bool,
+    pub cycles: bool,
+}
+
+impl PolishConfig {
+    pub fn new() -> Self {
+        return Self {
+            after_run: false,
+            cycles: false,
+        };
+    }
+}
+
+// Migration is off unless a topology is set. Every interval generations, each population sends
+// count keyboards to its destinations, where they replace the worst non-elite keyboards
+#[derive(Debug, Clone)]
+pub struct MigrationConfig {
+    pub topology: Option<Topology>,
+    pub interval: usize,
+    pub count: usize,
+    pub choice: MigrantChoice,
+}
+
+impl MigrationConfig {
+    pub fn new() -> Self {
+        return Self {
+            topology: None,
+            interval: 10,
+            count: 1,
+            choice: MigrantChoice::Best,
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.interval == 0 || self.count == 0 {
+            return Err(anyhow!(
+                "migration.interval and migration.count must be greater than zero"
+            ));
+        }
+
+        return Ok(());
+    }
+}
+
+// With evolve off, the meta-population holds a single population and never purges or reproduces.
+// Pair this with equal min/max population bounds to run one fixed-parameter population
+#[derive(Debug, Clone)]
+pub struct MetaConfig {
+    pub evolve: bool,
+    pub pop_size: usize,
+    pub to_remove: usize,
+    pub elite_cnt: usize,
+}
+
+impl MetaConfig {
+    pub fn new() -> Self {
+        return Self {
+            evolve: true,
+            pop_size: 20,
+            to_remove: 10,
+            elite_cnt: 1,
+        };
+    }
+
+    pub fn get_pop_size(&self) -> usize {
+        return if self.evolve {
+            self.pop_size
+        } else {
+            1
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !self.evolve {
+            return Ok(());
+        }
+
+        if self.elite_cnt == 0 || self.elite_cnt + self.to_remove > self.pop_size {
+            return Err(anyhow!(
+                "meta.elite_cnt ({}) must be at least one, and together with meta.to_remove ({}) \
+                 cannot exceed meta.pop_size ({})",
+                self.elite_cnt,
+                self.to_remove,
+                self.pop_size
+            ));
+        }
+
+        // Reproduction needs two parents left after the purge
+        if self.pop_size - self.to_remove < 2 {
+            return Err(anyhow!(
+                "meta.pop_size ({}) must be at least two more than meta.to_remove ({})",
+                self.pop_size,
+                self.to_remove
+            ));
+        }
+
+        return Ok(());
+    }
+}
+
+// Bounds for the hyperparameters each population is created with, and the chance that a child
+// population re-rolls a hyperparameter instead of inheriting it. Setting a min and max equal
+// fixes that hyperparameter
+#[derive(Debug, Clone)]
+pub struct PopulationConfig {
+    pub min_pop: usize,
+    pub max_pop: usize,
+    pub min_climb_pct: f64,
+    pub max_climb_pct: f64,
+    pub elite_cnt: usize,
+    pub min_mutation: usize,
+    pub max_mutation: usize,
+    pub min_score_decay: f64,
+    pub max_score_decay: f64,
+    pub min_k_temp: f64,
+    pub max_k_temp: f64,
+    pub mutation_rate: f64,
+}
+
+impl PopulationConfig {
+    pub fn new() -> Self {
+        return Self {
+            min_pop: 20,
+            max_pop: 100,
+            min_climb_pct: 0.1,
+            max_climb_pct: 0.4,
+            elite_cnt: 1,
+            min_mutation: 0,
+            max_mutation: 3,
+            min_score_decay: 0.9,
+            max_score_decay: 0.998,
+            min_k_temp: -31.162_892_36,
+            max_k_temp: -6.107_632_992,
+            mutation_rate: 0.05,
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        let ordered = self.min_pop <= self.max_pop
+            && self.min_climb_pct <= self.max_climb_pct
+            && self.min_mutation <= self.max_mutation
+            && self.min_score_decay <= self.max_score_decay
+            && self.min_k_temp <= self.max_k_temp;
+        if !ordered {
+            return Err(anyhow!(
+                "Each population.min_* setting must be less than or equal to its max"
+            ));
+        }
+
+        if self.elite_cnt == 0 {
+            return Err(anyhow!("population.elite_cnt must be at least one"));
+        }
+
+        if self.min_climb_pct <= 0.0_f64 || self.max_climb_pct >= 1.0_f64 {
+            return Err(anyhow!(
+                "Population climb percentages must be greater than 0 and less than 1"
+            ));
+        }
+
+        // The fewest climbers must still cover the elites, and the most climbers must leave room
+        // for new keyboards
+        let min_climbers = (self.min_pop as f64 * self.min_climb_pct).round() as usize;
+        let max_climbers = (self.min_pop as f64 * self.max_climb_pct).round() as usize;
+        if min_climbers < self.elite_cnt || max_climbers >= self.min_pop {
+            return Err(anyhow!(
+                "population.min_pop ({}) gives between {} and {} climbers. There must be at least \
+                 population.elite_cnt ({}) and fewer than min_pop",
+                self.min_pop,
+                min_climbers,
+                max_climbers,
+                self.elite_cnt
+            ));
+        }
+
+        if self.min_score_decay <= 0.0_f64 || self.max_score_decay > 1.0_f64 {
+            return Err(anyhow!(
+                "Population score decay must be greater than 0 and at most 1"
+            ));
+        }
+
+        if !(0.0_f64..=1.0_f64).contains(&self.mutation_rate) {
+            return Err(anyhow!(
+                "population.mutation_rate ({}) must be between 0 and 1",
+                self.mutation_rate
+            ));
+        }
+
+        return Ok(());
+    }
+}
+
+// How child populations derive their hyperparameters from their parents. The defaults copy one
+// parent's value, or occasionally resample uniformly from the bounds. log_k_temp varies k_temp in
+// log space instead, so it is resampled log-uniformly and Gaussian steps are proportional
+#[derive(Debug, Clone)]
+pub struct VariationConfig {
+    pub recombination: Recombination,
+    pub perturbation: Perturbation,
+    pub blx_alpha: f64,
+    pub sbx_eta: f64,
+    pub initial_sigma: f64,
+    pub log_k_temp: bool,
+}
+
+impl VariationConfig {
+    pub fn new() -> Self {
+        return Self {
+            recombination: Recombination::Pick,
+            perturbation: Perturbation::Resample,
+            blx_alpha: 0.5,
+            sbx_eta: 2.0,
+            initial_sigma: 0.1,
+            log_k_temp: false,
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.blx_alpha < 0.0_f64 || self.sbx_eta < 0.0_f64 {
+            return Err(anyhow!(
+                "variation.blx_alpha and variation.sbx_eta cannot be negative"
+            ));
+        }
+
+        if self.initial_sigma <= 0.0_f64 || self.initial_sigma > 0.5_f64 {
+            return Err(anyhow!(
+                "variation.initial_sigma ({}) must be greater than 0 and at most 0.5",
+                self.initial_sigma
+            ));
+        }
+
+        return Ok(());
+    }
+}
+
+// A saved swap table can seed every new population. load_decay scales the saved weights so the
+// prior gives way to new observations sooner. Zero keeps only the averages
+#[derive(Debug, Clone)]
+pub struct SwapTableConfig {
+    pub load: Option<PathBuf>,
+    pub load_decay: f64,
+    pub save: bool,
+    pub confidence: Confidence,
+    pub exploration: f64,
+}
+
+impl SwapTableConfig {
+    pub fn new() -> Self {
+        return Self {
+            load: None,
+            load_decay: 1.0,
+            save: true,
+            confidence: Confidence::None,
+            exploration: 1.0,
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !(0.0_f64..=1.0_f64).contains(&self.load_decay) {
+            return Err(anyhow!(
+                "swap_table.load_decay ({}) must be between 0 and 1",
+                self.load_decay
+            ));
+        }
+
+        if self.exploration < 0.0_f64 {
+            return Err(anyhow!(
+                "swap_table.exploration ({}) cannot be negative",
+                self.exploration
+            ));
+        }
+
+        return Ok(());
+    }
+}
+
+// The sweep command draws k_temp, score_decay, mutation, and climber percentage from the
+// population bounds. Population size is fixed at the middle of its bounds so runs are comparable
+#[derive(Debug, Clone)]
+pub struct SweepConfig {
+    pub mode: SweepMode,
+    pub grid_steps: usize,
+    pub samples: usize,
+    pub repeats: usize,
+    pub generations: usize,
+    pub target_score: Option<f64>,
+}
+
+impl SweepConfig {
+    pub fn new() -> Self {
+        return Self {
+            mode: SweepMode::Grid,
+            grid_steps: 2,
+            samples: 16,
+            repeats: 3,
+            generations: 20,
+            target_score: None,
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.grid_steps == 0 || self.samples == 0 {
+            return Err(anyhow!(
+                "sweep.grid_steps and sweep.samples must be greater than zero"
+            ));
+        }
+
+        if self.repeats == 0 || self.generations == 0 {
+            return Err(anyhow!(
+                "sweep.repeats and sweep.generations must be greater than zero"
+            ));
+        }
+
+        return Ok(());
+    }
+}
+
+// The physical layout used by the efficiency rules. Column offsets only apply to the column
+// stagger model. Each is how far that column sits above a straight grid, in key heights, for
+// columns 0 through 9 from the left pinky to the right pinky, and less than one key height either
+// way. The right pinky's extra columns share its offset
+#[derive(Debug, Clone)]
+pub struct GeometryConfig {
+    pub stagger: StaggerModel,
+    pub column_offsets: Vec<f64>,
+    pub board: Board,
+    pub iso_key: IsoKey,
+}
+
+impl GeometryConfig {
+    pub fn new() -> Self {
+        return Self {
+            stagger: StaggerModel::Row,
+            column_offsets: vec![0.0, 0.25, 0.5, 0.25, 0.125, 0.125, 0.25, 0.5, 0.25, 0.0],
+            board: Board::Ansi,
+            iso_key: IsoKey::Swappable,
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.column_offsets.len() != COLUMN_OFFSET_CNT {
+            return Err(anyhow!(
+                "geometry.column_offsets needs {COLUMN_OFFSET_CNT} values, found {}",
+                self.column_offsets.len()
+            ));
+        }
+
+        // A full key height would put a column's keys level with the next row
+        if self
+            .column_offsets
+            .iter()
+            .any(|o| return !o.is_finite() || o.abs() >= 1.0_f64)
+        {
+            return Err(anyhow!(
+                "geometry.column_offsets must all be greater than -1 and less than 1"
+            ));
+        }
+
+        if self.board == Board::Iso && self.stagger != StaggerModel::Row {
+            return Err(anyhow!(
+                "geometry.board = iso needs geometry.stagger = row. ISO boards are row staggered"
+            ));
+        }
+
+        // The static ISO key has to be a character the board does not already type
+        if let IsoKey::Static(key) = self.iso_key {
+            let chars = [key.get_base(), key.get_shift()];
+            if STATIC_KEYS
+                .iter()
+                .chain(SWAPPABLE_KEYS.iter())
+                .any(|k| return chars.contains(&k.0) || chars.contains(&k.1))
+            {
+                return Err(anyhow!(
+                    "geometry.iso_key {} is already on the board",
+                    self.iso_key
+                ));
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+// Characters typed with a thumb. Without any, space is unmapped and breaks the typing history
+// the same way as any other character the layout does not hold
+#[derive(Debug, Clone)]
+pub struct ThumbConfig {
+    pub keys: Vec<ThumbKey>,
+}
+
+impl ThumbConfig {
+    pub fn new() -> Self {
+        return Self { keys: Vec::new() };
+    }
+
+    // Swappable keys are placed by the optimizer, so they cannot also be pinned to a thumb
+    fn validate(&self) -> Result<()> {
+        for (i, thumb_key) in self.keys.iter().enumerate() {
+            let key = thumb_key.get_key();
+            if SWAPPABLE_KEYS
+                .iter()
+                .any(|k| return k.0 == key || k.1 == key)
+            {
+                return Err(anyhow!(
+                    "thumb.keys cannot hold \"{}\", which the optimizer places",
+                    key
+                ));
+            }
+
+            for other in self.keys.iter().skip(i + 1) {
+                if other.get_key() == key || other.get_pos() == thumb_key.get_pos() {
+                    return Err(anyhow!(
+                        "thumb.keys assigns \"{}\" or position {} more than once",
+                        key,
+                        thumb_key.get_pos()
+                    ));
+                }
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+// Small boards put the number row and the symbols on layers, each reached by holding a thumb key.
+// Layer keys can go anywhere on any layer. The costs multiply the efficiency of a key typed on a
+// layer: switch_cost when the previous key was on a different layer, hold_cost when it was on the
+// same one
+#[derive(Debug, Clone)]
+pub struct LayerConfig {
+    pub count: usize,
+    pub access: Vec<ThumbPos>,
+    pub switch_cost: f64,
+    pub hold_cost: f64,
+}
+
+impl LayerConfig {
+    pub fn new() -> Self {
+        return Self {
+            count: 0,
+            access: vec![ThumbPos::LeftReach, ThumbPos::RightReach],
+            switch_cost: 0.7,
+            hold_cost: 0.9,
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.count > MAX_LAYERS {
+            return Err(anyhow!(
+                "layer.count ({}) cannot be more than {MAX_LAYERS}",
+                self.count
+            ));
+        }
+
+        if self.access.len() < self.count {
+            return Err(anyhow!(
+                "layer.access needs a thumb position for each of the {} layers",
+                self.count
+            ));
+        }
+
+        let access = &self.access[..self.count];
+        for (i, pos) in access.iter().enumerate() {
+            if access.iter().skip(i + 1).any(|p| return p == pos) {
+                return Err(anyhow!("layer.access uses {pos} more than once"));
+            }
+        }
+
+        let costs = [self.switch_cost, self.hold_cost];
+        if costs.iter().any(|c| return *c <= 0.0_f64 || *c > 1.0_f64) {
+            return Err(anyhow!(
+                "layer.switch_cost and layer.hold_cost must be greater than 0 and at most 1"
+            ));
+        }
+
+        return Ok(());
+    }
+}
+
+// Scores each keystroke by how far the finger travels to reach it, using each slot's position on
+// the board from the geometry section. A finger travels from its home key, or from the previous
+// key if it typed that one too. Same-finger bigrams add lateral_weight times their sideways
+// distance. Travel is divided by fingers.strength, so weak fingers pay more for the same distance.
+// distance_weight blends the result with the rule-based score. 0 leaves the rules alone
+#[derive(Debug, Clone)]
+pub struct EffortConfig {
+    pub distance_weight: f64,
+    pub distance_scale: f64,
+    pub lateral_weight: f64,
+}
+
+impl EffortConfig {
+    pub fn new() -> Self {
+        return Self {
+            distance_weight: 0.0,
+            distance_scale: 0.5,
+            lateral_weight: 0.5,
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !(0.0_f64..=1.0_f64).contains(&self.distance_weight) {
+            return Err(anyhow!("effort.distance_weight must be between 0 and 1"));
+        }
+
+        if !(self.distance_scale.is_finite() && self.distance_scale >= 0.0_f64) {
+            return Err(anyhow!("effort.distance_scale cannot be negative"));
+        }
+
+        if !(self.lateral_weight.is_finite() && self.lateral_weight >= 0.0_f64) {
+            return Err(anyhow!("effort.lateral_weight cannot be negative"));
+        }
+
+        return Ok(());
+    }
+}
+
+// Which finger types each key, and how hard each finger should work. columns assigns every column
+// of the main rows, mods apply named changes such as the angle mod on top of it, and overrides
+// reassigns single slots last. With load_targets set, the score drops by load_weight times how
+// far the share of keystrokes on each finger is from its target share
+#[derive(Debug, Clone)]
+pub struct FingerConfig {
+    pub columns: Vec<FingerId>,
+    pub mods: Vec<ErgoMod>,
+    pub overrides: Vec<FingerOverride>,
+    pub strength: Vec<f64>,
+    pub load_targets: Option<Vec<f64>>,
+    pub load_weight: f64,
+}
+
+impl FingerConfig {
+    pub fn new() -> Self {
+        return Self {
+            columns: (0..FINGER_COLUMN_CNT).map(FingerId::from_col).collect(),
+            mods: Vec::new(),
+            overrides: Vec::new(),
+            strength: DEFAULT_STRENGTH.to_vec(),
+            load_targets: None,
+            load_weight: 1.0,
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        if self.columns.len() != FINGER_COLUMN_CNT {
+            return Err(anyhow!(
+                "fingers.columns needs {FINGER_COLUMN_CNT} values, found {}",
+                self.columns.len()
+            ));
+        }
+
+        let assigned = self
+            .columns
+            .iter()
+            .copied()
+            .chain(self.overrides.iter().map(|o| return o.get_finger()));
+        for finger_id in assigned {
+            if finger_id.get_finger() == Finger::Thumb {
+                return Err(anyhow!(
+                    "fingers.columns and fingers.overrides cannot assign a thumb ({finger_id})"
+                ));
+            }
+        }
+
+        for (i, ergo_mod) in self.mods.iter().enumerate() {
+            if self.mods.iter().skip(i + 1).any(|m| return m == ergo_mod) {
+                return Err(anyhow!("fingers.mods lists {ergo_mod} more than once"));
+            }
+        }
+
+        for (i, finger_override) in self.overrides.iter().enumerate() {
+            let slot = finger_override.get_slot();
+            if self
+                .overrides
+                .iter()
+                .skip(i + 1)
+                .any(|o| return o.get_slot() == slot)
+            {
+                return Err(anyhow!(
+                    "fingers.overrides reassigns row {}, col {} more than once",
+                    slot.get_row(),
+                    slot.get_col()
+                ));
+            }
+        }
+
+        if self.strength.len() != FINGER_CNT {
+            return Err(anyhow!(
+                "fingers.strength needs {FINGER_CNT} values, found {}",
+                self.strength.len()
+            ));
+        }
+
+        if self
+            .strength
+            .iter()
+            .any(|s| return !s.is_finite() || *s <= 0.0_f64)
+        {
+            return Err(anyhow!("fingers.strength values must be greater than 0"));
+        }
+
+        if let Some(targets) = &self.load_targets {
+            if targets.len() != FINGER_CNT {
+                return Err(anyhow!(
+                    "fingers.load_targets needs {FINGER_CNT} values, found {}",
+                    targets.len()
+                ));
+            }
+
+            if targets
+                .iter()
+                .any(|t| return !t.is_finite() || *t < 0.0_f64)
+                || targets.iter().all(|t| return *t <= 0.0_f64)
+            {
+                return Err(anyhow!(
+                    "fingers.load_targets cannot be negative, and at least one must be above 0"
+                ));
+            }
+        }
+
+        if !(0.0_f64..=1.0_f64).contains(&self.load_weight) {
+            return Err(anyhow!("fingers.load_weight must be between 0 and 1"));
+        }
+
+        return Ok(());
+    }
+}
+
+pub fn initialize_config(path: Option<&Path>) -> Result<()> {
+    let config = if let Some(config_path) = path {
+        load_config(config_path)?
+    } else {
+        Config::new()
+    };
+
+    config.validate()?;
+
+    CONFIG
+        .set(config)
+        .map_err(|e| anyhow!(format!("Failed to initialize CONFIG: {:?}", e)))?;
+
+    return Ok(());
+}
+
+// Keys of other languages, each put in place of a board key. The board keeps its shape, so an
+// accented letter has to displace a key that the language needs less
+#[derive(Debug, Clone)]
+pub struct CharsetConfig {
+    pub keys: Vec<CharsetKey>,
+}
+
+impl CharsetConfig {
+    pub fn new() -> Self {
+        return Self { keys: Vec::new() };
+    }
+
+    fn validate(&self) -> Result<()> {
+        let board_keys: Vec<(char, char)> = STATIC_KEYS
+            .iter()
+            .chain(SWAPPABLE_KEYS.iter())
+            .copied()
+            .collect();
+
+        for (i, charset_key) in self.keys.iter().enumerate() {
+            let old = charset_key.get_old();
+            if old == NEWLINE.0 {
+                return Err(anyhow!("charset.keys {charset_key} cannot replace Enter"));
+            }
+
+            if !board_keys.iter().any(|k| return k.0 == old) {
+                return Err(anyhow!(
+                    "charset.keys {charset_key} does not replace a key of the board. Name it by \
+                     its unshifted character"
+                ));
+            }
+
+            let key = charset_key.get_key();
+            let chars = [key.get_base(), key.get_shift()];
+            if board_keys
+                .iter()
+                .any(|k| return chars.contains(&k.0) || chars.contains(&k.1))
+            {
+                return Err(anyhow!(
+                    "charset.keys {charset_key} is already on the board"
+                ));
+            }
+
+            for other in self.keys.iter().skip(i + 1) {
+                let other_key = other.get_key();
+                if other.get_old() == old
+                    || chars.contains(&other_key.get_base())
+                    || chars.contains(&other_key.get_shift())
+                {
+                    return Err(anyhow!(
+                        "charset.keys replaces \"{}\" or places {} more than once",
+                        escape_key(old),
+                        escape_key(key.get_base())
+                    ));
+                }
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+// Characters typed through a dead key instead of a key of their own
+#[derive(Debug, Clone)]
+pub struct DeadKeyConfig {
+    pub sequences: Vec<DeadKeySeq>,
+}
+
+impl DeadKeyConfig {
+    pub fn new() -> Self {
+        return Self {
+            sequences: Vec::new(),
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        for (i, seq) in self.sequences.iter().enumerate() {
+            if xkb_dead_keysym(seq.get_dead()).is_err() {
+                return Err(anyhow!(
+                    "dead_keys.sequences {seq} uses {} as a dead key, which has no XKB dead \
+                     keysym. Use an accent, such as ` ' ^ ~ \"",
+                    escape_key(seq.get_dead())
+                ));
+            }
+
+            if self.is_dead(seq.get_composed()) {
+                return Err(anyhow!(
+                    "dead_keys.sequences {seq} composes a character that is also a dead key"
+                ));
+            }
+
+            if self
+                .sequences
+                .iter()
+                .skip(i + 1)
+                .any(|other| return other.get_composed() == seq.get_composed())
+            {
+                return Err(anyhow!(
+                    "dead_keys.sequences composes {} more than once",
+                    escape_key(seq.get_composed())
+                ));
+            }
+        }
+
+        return Ok(());
+    }
+
+    pub fn is_dead(&self, c: char) -> bool {
+        return self.sequences.iter().any(|seq| return seq.get_dead() == c);
+    }
+}
+
+// Several corpora scored together, such as one per language. Empty reads every file in the corpus
+// directory as one corpus
+#[derive(Debug, Clone)]
+pub struct CorpusConfig {
+    pub sets: Vec<CorpusSpec>,
+    pub objective: CorpusObjective,
+}
+
+impl CorpusConfig {
+    pub fn new() -> Self {
+        return Self {
+            sets: Vec::new(),
+            objective: CorpusObjective::Weighted,
+        };
+    }
+
+    fn validate(&self) -> Result<()> {
+        for (i, spec) in self.sets.iter().enumerate() {
+            let name = spec.get_name();
+            if name.contains(['/', '\\']) || name == "." || name == ".." {
+                return Err(anyhow!(
+                    "corpus.sets {spec} must name a directory directly inside the corpus directory"
+                ));
+            }
+
+            if !spec.get_weight().is_finite() || spec.get_weight() <= 0.0_f64 {
+                return Err(anyhow!("corpus.sets {spec} must have a weight above 0"));
+            }
+
+            if let Some(floor) = spec.get_floor()
+                && (!floor.is_finite() || floor <= 0.0_f64)
+            {
+                return Err(anyhow!("corpus.sets {spec} must have a floor above 0"));
+            }
+
+            if self
+                .sets
+                .iter()
+                .skip(i + 1)
+                .any(|other| return other.get_name() == name)
+            {
+                return Err(anyhow!("corpus.sets lists {name} more than once"));
+            }
+        }
+
+        return Ok(());
+    }
+}
+
+pub fn get_config() -> &'static Config {
+    return CONFIG.get().expect("CONFIG not initialized");
+}
+
+fn load_config(path: &Path) -> Result<Config> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            let err_string = format!("Unable to open {} -- {}", path.display(), e);
+            return Err(anyhow!(err_string));
+        }
+    };
+
+    let mut config = Config::new();
+
+    for (i, raw_line) in contents.lines().enumerate() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let Some((key, value)) = line.split_once('=') else {
+            return Err(anyhow!(
+                "Line {} of {} is not \"key = value\"",
+                i + 1,
+                path.display()
+            ));
+        };
+
+        config.apply_entry(key.trim(), value.trim())?;
+    }
+
+    return Ok(config);
+}
+
+fn parse_value<T: FromStr>(key: &str, value: &str) -> Result<T>
+where
+    T::Err: core::fmt::Display,
+{
+    return value
+        .parse::<T>()
+        .map_err(|e| return anyhow!("Invalid value \"{value}\" for {key} -- {e}"));
+}
+
+fn parse_opt<T: FromStr>(key: &str, value: &str) -> Result<Option<T>>
+where
+    T::Err: core::fmt::Display,
+{
+    if value.eq_ignore_ascii_case("none") {
+        return Ok(None);
+    }
+
+    return Ok(Some(parse_value(key, value)?));
+}
+
+// Comma separated values, such as "0, 0.25, 0.5"
+fn parse_list<T: FromStr>(key: &str, value: &str) -> Result<Vec<T>>
+where
+    T::Err: core::fmt::Display,
+{
+    return value
+        .split(',')
+        .map(|item| return parse_value(key, item.trim()))
+        .collect();
+}
@@ -1,26 +1,227 @@
-use std::{
-    env,
-    fs::{self},
-    path::PathBuf,
-    sync::OnceLock,
+extern crate alloc;
+
+use {
+    alloc::collections::BTreeMap,
+    core::{fmt, ops::Range, str::FromStr},
+    std::{
+        env,
+        fs::{self, File},
+        path::PathBuf,
+        sync::OnceLock,
+    },
 };
 
-use anyhow::{Result, anyhow};
+use anyhow::{Error, Result, anyhow};
+
+use crate::{config::get_config, keyboard::get_qwerty_baselines, utils::write_log};
 
 // FUTURE: Should be able to make this an Arc for multi-threading
 pub static CORPUS: OnceLock<Vec<String>> = OnceLock::new();
+// Share of all corpus characters taken up by each character
+pub static CHAR_FREQS: OnceLock<BTreeMap<char, f64>> = OnceLock::new();
+// Empty unless corpus.sets is given, in which case each set's entries are a run of CORPUS
+pub static CORPUS_SETS: OnceLock<Vec<CorpusSet>> = OnceLock::new();
+
+// With several corpora, a score is a percentage of Qwerty's score on the same corpora
+pub const QWERTY_PCT: f64 = 100.0;
+
+// How the scores of several corpora are combined into one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CorpusObjective {
+    Weighted,
+    Minimax,
+}
+
+impl fmt::Display for CorpusObjective {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            CorpusObjective::Weighted => write!(f, "weighted"),
+            CorpusObjective::Minimax => write!(f, "minimax"),
+        };
+    }
+}
+
+impl FromStr for CorpusObjective {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "weighted" => Ok(CorpusObjective::Weighted),
+            "minimax" => Ok(CorpusObjective::Minimax),
+            _ => Err(anyhow!("expected weighted or minimax")),
+        };
+    }
+}
+
+// A subdirectory of the corpus directory, scored as a corpus of its own. Written as name,
+// name:weight, or name:weight:floor. The floor is the lowest acceptable score as a share of
+// Qwerty's, so 1.0 asks for no worse than Qwerty
+#[derive(Debug, Clone, PartialEq)]
+pub struct CorpusSpec {
+    name: String,
+    weight: f64,
+    floor: Option<f64>,
+}
+
+impl CorpusSpec {
+    pub fn get_name(&self) -> &str {
+        return &self.name;
+    }
+
+    pub fn get_weight(&self) -> f64 {
+        return self.weight;
+    }
+
+    pub fn get_floor(&self) -> Option<f64> {
+        return self.floor;
+    }
+}
+
+impl fmt::Display for CorpusSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.name, self.weight)?;
+        if let Some(floor) = self.floor {
+            write!(f, ":{floor}")?;
+        }
 
-pub fn initialize_corpus() -> Result<()> {
+        return Ok(());
+    }
+}
+
+impl FromStr for CorpusSpec {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':');
+        let name = parts.next().unwrap_or_default().trim();
+        if name.is_empty() {
+            return Err(anyhow!("expected name, name:weight, or name:weight:floor"));
+        }
+
+        let weight = match parts.next() {
+            Some(weight_str) => weight_str.trim().parse::<f64>()?,
+            None => 1.0_f64,
+        };
+        let floor = match parts.next() {
+            Some(floor_str) => Some(floor_str.trim().parse::<f64>()?),
+            None => None,
+        };
+
+        if parts.next().is_some() {
+            return Err(anyhow!("expected name, name:weight, or name:weight:floor"));
+        }
+
+        return Ok(Self {
+            name: name.to_string(),
+            weight,
+            floor,
+        });
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CorpusSet {
+    spec: CorpusSpec,
+    entries: Range<usize>,
+}
+
+impl CorpusSet {
+    pub fn get_spec(&self) -> &CorpusSpec {
+        return &self.spec;
+    }
+
+    pub fn get_entries(&self) -> &'static [String] {
+        return &get_corpus()[self.entries.clone()];
+    }
+}
+
+pub fn initialize_corpus(log_handle: &mut File) -> Result<()> {
     let corpus_dir = get_corpus_dir()?;
-    let corpus = load_corpus(&corpus_dir)?;
-    if corpus.is_empty() {
+    let specs = &get_config().corpus.sets;
+
+    let mut loaded: Vec<String> = Vec::new();
+    let mut corpus_sets: Vec<CorpusSet> = Vec::new();
+    if specs.is_empty() {
+        loaded = load_corpus(&corpus_dir)?;
+    } else {
+        for spec in specs {
+            let start = loaded.len();
+            loaded.extend(load_corpus(&corpus_dir.join(spec.get_name()))?);
+            corpus_sets.push(CorpusSet {
+                spec: spec.clone(),
+                entries: start..loaded.len(),
+            });
+        }
+    }
+
+    if loaded.is_empty() {
         return Err(anyhow!("No corpus entries in initialize_corpus"));
     }
 
+    let corpus = expand_dead_keys(loaded);
+    check_charsets(log_handle, &corpus, &corpus_sets)?;
+
+    let char_freqs = get_char_freq_map(&corpus);
+    CHAR_FREQS
+        .set(char_freqs)
+        .map_err(|e| anyhow!(format!("Failed to initialize CHAR_FREQS: {:?}", e)))?;
+
     CORPUS
         .set(corpus)
         .map_err(|e| anyhow!(format!("Failed to initialize CORPUS: {:?}", e)))?;
 
+    CORPUS_SETS
+        .set(corpus_sets)
+        .map_err(|e| anyhow!(format!("Failed to initialize CORPUS_SETS: {:?}", e)))?;
+
+    // Scores are shares of Qwerty's, so Qwerty has to type some of every corpus
+    for (set, baseline) in get_corpus_sets().iter().zip(get_qwerty_baselines()) {
+        if !baseline.is_finite() || *baseline <= 0.0_f64 {
+            return Err(anyhow!(
+                "Qwerty types none of corpus {}, so it has no baseline to score against",
+                set.spec.get_name()
+            ));
+        }
+    }
+
+    return Ok(());
+}
+
+// Each language's letters should be on the board, through charset.keys or dead_keys.sequences.
+// Missing ones are logged with how often they appear, and are left out of the score like any other
+// character the board does not type
+fn check_charsets(
+    log_handle: &mut File,
+    corpus: &[String],
+    corpus_sets: &[CorpusSet],
+) -> Result<()> {
+    let typed = get_config().get_typed_chars();
+    for set in corpus_sets {
+        let mut missing: BTreeMap<char, usize> = BTreeMap::new();
+        for entry in &corpus[set.entries.clone()] {
+            for c in entry
+                .chars()
+                .filter(|c| return c.is_alphabetic() && !typed.contains(c))
+            {
+                *missing.entry(c).or_insert(0) += 1;
+            }
+        }
+
+        if !missing.is_empty() {
+            let letters: Vec<String> = missing
+                .iter()
+                .map(|(c, cnt)| return format!("{c} ({cnt})"))
+                .collect();
+            let message = format!(
+                "Corpus {} has letters the board does not type: {}. Add them with charset.keys or \
+                 dead_keys.sequences",
+                set.spec.get_name(),
+                letters.join(", ")
+            );
+            write_log(log_handle, &message)?;
+        }
+    }
+
     return Ok(());
 }
 
@@ -28,6 +229,99 @@ pub fn get_corpus() -> &'static Vec<String> {
     return CORPUS.get().expect("CORPUS not initialized");
 }
 
+pub fn get_corpus_sets() -> &'static Vec<CorpusSet> {
+    return CORPUS_SETS.get().expect("CORPUS_SETS not initialized");
+}
+
+// The entries of each corpus, or the whole corpus without corpus.sets. Keyboard::eval scores each
+// on its own, so typing history does not carry from one to the next
+pub fn get_corpus_groups() -> Vec<&'static [String]> {
+    let sets = get_corpus_sets();
+    if sets.is_empty() {
+        return vec![get_corpus().as_slice()];
+    }
+
+    return sets.iter().map(|set| return set.get_entries()).collect();
+}
+
+// Takes each corpus's score as a share of Qwerty's. A corpus below its floor scales the combined
+// score down by how far it falls short, so the floor holds without a cliff in the score
+pub fn combine_corpus_scores(ratios: &[f64]) -> f64 {
+    let sets = get_corpus_sets();
+    let mut combined = match get_config().corpus.objective {
+        CorpusObjective::Weighted => {
+            let total_weight = sets
+                .iter()
+                .fold(0.0_f64, |acc, set| return acc + set.spec.get_weight());
+            sets.iter().zip(ratios).fold(0.0_f64, |acc, (set, ratio)| {
+                return set.spec.get_weight().mul_add(*ratio, acc);
+            }) / total_weight
+        }
+        CorpusObjective::Minimax => ratios.iter().copied().fold(f64::INFINITY, f64::min),
+    };
+
+    for (set, ratio) in sets.iter().zip(ratios) {
+        if let Some(floor) = set.spec.get_floor()
+            && *ratio < floor
+        {
+            combined *= ratio / floor;
+        }
+    }
+
+    return combined * QWERTY_PCT;
+}
+
+pub fn get_char_freqs() -> &'static BTreeMap<char, f64> {
+    return CHAR_FREQS.get().expect("CHAR_FREQS not initialized");
+}
+
+// Composed characters are replaced by the keys that type them, so scoring, comparisons, and
+// reports all see the dead key presses. A dead key's own character takes the dead key then space
+// PERF: Done once here rather than per character in every Keyboard::eval
+fn expand_dead_keys(corpus: Vec<String>) -> Vec<String> {
+    let dead_keys = &get_config().dead_keys;
+    if dead_keys.sequences.is_empty() {
+        return corpus;
+    }
+
+    let mut presses: BTreeMap<char, [char; 2]> = BTreeMap::new();
+    for seq in &dead_keys.sequences {
+        presses.insert(seq.get_composed(), [seq.get_dead(), seq.get_base()]);
+        presses.insert(seq.get_dead(), [seq.get_dead(), ' ']);
+    }
+
+    return corpus
+        .into_iter()
+        .map(|entry| {
+            let mut expanded = String::with_capacity(entry.len());
+            for c in entry.chars() {
+                if let Some(keys) = presses.get(&c) {
+                    expanded.extend(keys);
+                } else {
+                    expanded.push(c);
+                }
+            }
+
+            return expanded;
+        })
+        .collect();
+}
+
+fn get_char_freq_map(corpus: &[String]) -> BTreeMap<char, f64> {
+    let mut counts: BTreeMap<char, usize> = BTreeMap::new();
+    for entry in corpus {
+        for c in entry.chars() {
+            *counts.entry(c).or_insert(0) += 1;
+        }
+    }
+
+    let total = counts.values().sum::<usize>().max(1) as f64;
+    return counts
+        .into_iter()
+        .map(|(c, cnt)| return (c, cnt as f64 / total))
+        .collect();
+}
+
 fn get_corpus_dir() -> Result<PathBuf> {
     let corpus_dir_parent: PathBuf = if cfg!(debug_assertions) {
         let cargo_root: String = env::var("CARGO_MANIFEST_DIR")?;
@@ -0,0 +1,252 @@
+extern crate alloc;
+
+use {
+    alloc::collections::{BTreeMap, BTreeSet},
+    core::{fmt, str::FromStr},
+};
+
+use {
+    anyhow::{Error, anyhow},
+    rand::{Rng as _, rngs::SmallRng, seq::SliceRandom as _},
+};
+
+use crate::{
+    kb_builders::{get_iso_swap_slot, get_swappable_keys},
+    keyboard::Keyboard,
+    keys,
+    mapped_swap::shuffle_check,
+    structs::{Key, Slot},
+    swappable_keys,
+};
+
+swappable_keys!();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CrossoverOp {
+    Pmx,
+    Order,
+    Cycle,
+    KeepAgreed,
+}
+
+impl CrossoverOp {
+    pub fn get_name(self) -> &'static str {
+        return match self {
+            CrossoverOp::Pmx => "PMX",
+            CrossoverOp::Order => "Order",
+            CrossoverOp::Cycle => "Cycle",
+            CrossoverOp::KeepAgreed => "Keep Agreed",
+        };
+    }
+}
+
+impl fmt::Display for CrossoverOp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(f, "{}", self.get_name());
+    }
+}
+
+impl FromStr for CrossoverOp {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "pmx" => Ok(CrossoverOp::Pmx),
+            "order" | "ox" => Ok(CrossoverOp::Order),
+            "cycle" | "cx" => Ok(CrossoverOp::Cycle),
+            "keep_agreed" => Ok(CrossoverOp::KeepAgreed),
+            _ => Err(anyhow!("expected pmx, order, cycle, or keep_agreed")),
+        };
+    }
+}
+
+// Only the swappable keys are recombined. Each parent is read as a permutation of those keys over
+// the slots they occupy, so the static keys are carried over from parent_a unchanged. Operators
+// can put a key somewhere it is not allowed to go, so the child is repaired with valid swaps
+// afterwards. A swappable ISO key's blank is recombined along with them. Returns None if the
+// parents do not share the same swappable slots or the child cannot be repaired
+pub fn crossover(
+    rng: &mut SmallRng,
+    op: CrossoverOp,
+    parent_a: &Keyboard,
+    parent_b: &Keyboard,
+) -> Option<BTreeMap<Slot, Key>> {
+    let mut swappable: BTreeSet<Key> = get_swappable_keys(&SWAPPABLE_KEYS).into_iter().collect();
+    if get_iso_swap_slot().is_some() {
+        swappable.insert(Key::BLANK);
+    }
+
+    let slots: Vec<Slot> = parent_a
+        .get_key_slots()
+        .iter()
+        .filter(|(_, key)| return swappable.contains(key))
+        .map(|(slot, _)| return *slot)
+        .collect();
+
+    let genes_a: Vec<Key> = slots
+        .iter()
+        .map(|s| return parent_a.get_key_slots()[s])
+        .collect();
+    let genes_b: Vec<Key> = slots
+        .iter()
+        .map(|s| return parent_b.get_key_slots().get(s).copied())
+        .collect::<Option<Vec<Key>>>()?;
+
+    let keys_b: BTreeSet<Key> = genes_b.iter().copied().collect();
+    if keys_b != swappable || genes_a.len() != genes_b.len() {
+        return None;
+    }
+
+    let mut child = match op {
+        CrossoverOp::Pmx => pmx(rng, &genes_a, &genes_b),
+        CrossoverOp::Order => order(rng, &genes_a, &genes_b),
+        CrossoverOp::Cycle => cycle(rng, &genes_a, &genes_b),
+        CrossoverOp::KeepAgreed => keep_agreed(rng, &genes_a, &genes_b),
+    };
+
+    if !repair(rng, &mut child, &slots, parent_a.get_valid_slots()) {
+        return None;
+    }
+
+    let mut key_slots = parent_a.get_key_slots().clone();
+    for (slot, key) in slots.iter().zip(child) {
+        key_slots.insert(*slot, key);
+    }
+
+    return Some(key_slots);
+}
+
+fn get_segment(rng: &mut SmallRng, len: usize) -> (usize, usize) {
+    let a = rng.random_range(0..len);
+    let b = rng.random_range(0..len);
+
+    return (a.min(b), a.max(b) + 1);
+}
+
+// Partially mapped crossover. A segment is copied from parent_a. Everything else comes from
+// parent_b, following the mapping between the two segments whenever parent_b's key is already in
+// use
+fn pmx(rng: &mut SmallRng, genes_a: &[Key], genes_b: &[Key]) -> Vec<Key> {
+    let (lo, hi) = get_segment(rng, genes_a.len());
+    let segment_pos: BTreeMap<Key, usize> = (lo..hi).map(|i| return (genes_a[i], i)).collect();
+
+    let mut child: Vec<Key> = genes_b.to_vec();
+    child[lo..hi].copy_from_slice(&genes_a[lo..hi]);
+
+    for i in (0..lo).chain(hi..genes_a.len()) {
+        let mut key = genes_b[i];
+        while let Some(j) = segment_pos.get(&key) {
+            key = genes_b[*j];
+        }
+
+        child[i] = key;
+    }
+
+    return child;
+}
+
+// A segment is copied from parent_a. The remaining keys are filled in the order they appear in
+// parent_b, starting after the segment and wrapping around
+fn order(rng: &mut SmallRng, genes_a: &[Key], genes_b: &[Key]) -> Vec<Key> {
+    let len = genes_a.len();
+    let (lo, hi) = get_segment(rng, len);
+    let used: BTreeSet<Key> = genes_a[lo..hi].iter().copied().collect();
+
+    let mut fill = (0..len)
+        .map(|i| return genes_b[(hi + i) % len])
+        .filter(|k| return !used.contains(k));
+
+    let mut child: Vec<Key> = genes_a.to_vec();
+    for i in (hi..len).chain(0..lo) {
+        child[i] = fill.next().expect("Order crossover ran out of keys");
+    }
+
+    return child;
+}
+
+// Positions are split into cycles that map between the parents. Alternating cycles are taken
+// from each parent, so every key stays in a position one of the parents had it in. The parent
+// that starts is chosen at random so the operator is not biased toward parent_a
+fn cycle(rng: &mut SmallRng, genes_a: &[Key], genes_b: &[Key]) -> Vec<Key> {
+    let pos_a: BTreeMap<Key, usize> = genes_a
+        .iter()
+        .enumerate()
+        .map(|(i, k)| return (*k, i))
+        .collect();
+
+    let mut child: Vec<Option<Key>> = vec![None; genes_a.len()];
+    let mut from_a: bool = rng.random_bool(0.5);
+
+    for start in 0..genes_a.len() {
+        if child[start].is_some() {
+            continue;
+        }
+
+        let mut i = start;
+        loop {
+            child[i] = Some(if from_a {
+                genes_a[i]
+            } else {
+                genes_b[i]
+            });
+            i = pos_a[&genes_b[i]];
+            if i == start {
+                break;
+            }
+        }
+
+        from_a = !from_a;
+    }
+
+    return child
+        .into_iter()
+        .map(|k| return k.expect("Cycle crossover left a position empty"))
+        .collect();
+}
+
+// Keys that both parents put in the same slot stay there. The rest are shuffled into the
+// remaining slots
+fn keep_agreed(rng: &mut SmallRng, genes_a: &[Key], genes_b: &[Key]) -> Vec<Key> {
+    let open: Vec<usize> = (0..genes_a.len())
+        .filter(|i| return genes_a[*i] != genes_b[*i])
+        .collect();
+
+    let mut loose: Vec<Key> = open.iter().map(|i| return genes_a[*i]).collect();
+    loose.shuffle(rng);
+
+    let mut child: Vec<Key> = genes_a.to_vec();
+    for (i, key) in open.into_iter().zip(loose) {
+        child[i] = key;
+    }
+
+    return child;
+}
+
+// Any key outside its valid slots is swapped with a randomly chosen key where both end up valid.
+// Each swap fixes the current position without breaking the other, so a single pass is enough
+fn repair(
+    rng: &mut SmallRng,
+    genes: &mut [Key],
+    slots: &[Slot],
+    valid_slots: &BTreeMap<Key, Vec<Slot>>,
+) -> bool {
+    for i in 0..genes.len() {
+        if valid_slots[&genes[i]].contains(&slots[i]) {
+            continue;
+        }
+
+        let mut candidates: Vec<usize> = (0..genes.len()).collect();
+        candidates.shuffle(rng);
+
+        let Some(j) = candidates
+            .into_iter()
+            .find(|j| return shuffle_check(valid_slots, slots[i], genes[i], slots[*j], genes[*j]))
+        else {
+            return false;
+        };
+
+        genes.swap(i, j);
+    }
+
+    return true;
+}
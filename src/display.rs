@@ -1,5 +1,6 @@
 use std::io;
 use std::io::{Write as _, stdout};
+use std::sync::OnceLock;
 
 use crate::population::Population;
 
@@ -10,7 +11,10 @@ use crossterm::{
     terminal::{Clear, ClearType},
 };
 
-use crate::keyboard::Keyboard;
+use crate::{config::get_config, diversity::DiversityStats, keyboard::Keyboard};
+
+// Set once for commands that run populations without the live display, such as sweeps
+static QUIET: OnceLock<bool> = OnceLock::new();
 
 const OFFSET_Y: u16 = 1;
 
@@ -38,7 +42,12 @@ const ITER_LEN: usize = ITER_NAME.len();
 const ITER_NUM_X: u16 = ITER_LEN as u16;
 const ITER_Y: u16 = KB_BOT_Y + 2;
 
-const CUR_POP_HEADER_Y: u16 = ITER_Y + 2;
+const META_DIV_NAME: &str = "Meta Diversity: ";
+const META_DIV_LEN: usize = META_DIV_NAME.len();
+const META_DIV_NUM_X: u16 = META_DIV_LEN as u16;
+const META_DIV_Y: u16 = ITER_Y + 1;
+
+const CUR_POP_HEADER_Y: u16 = META_DIV_Y + 2;
 const CUR_POP_STATS_Y: u16 = CUR_POP_HEADER_Y + 1;
 
 const CUR_AVG_NAME: &str = "Average Climber Score: ";
@@ -46,10 +55,15 @@ const CUR_AVG_LEN: usize = CUR_AVG_NAME.len();
 const CUR_AVG_NUM_X: u16 = CUR_AVG_LEN as u16;
 const CUR_AVG_Y: u16 = CUR_POP_STATS_Y + 1;
 
+const CUR_DIV_NAME: &str = "Diversity: ";
+const CUR_DIV_LEN: usize = CUR_DIV_NAME.len();
+const CUR_DIV_NUM_X: u16 = CUR_DIV_LEN as u16;
+const CUR_DIV_Y: u16 = CUR_AVG_Y + 1;
+
 const EVAL_NAME: &str = "Evaluating: ";
 const EVAL_LEN: usize = EVAL_NAME.len();
 const EVAL_NUM_X: u16 = EVAL_LEN as u16;
-const EVAL_Y: u16 = CUR_AVG_Y + 1;
+const EVAL_Y: u16 = CUR_DIV_Y + 1;
 
 const CLIMB_HEADER_Y: u16 = EVAL_Y + 2;
 const CLIMB_INFO_Y: u16 = CLIMB_HEADER_Y + 1;
@@ -70,6 +84,10 @@ const CURSOR_Y: u16 = DVORAK_Y + 1;
 // FUTURE: This probably all needs to be redone, but don't want to get deep into it until I know
 // what the outputs actually are
 pub fn initial_dsp() -> io::Result<()> {
+    if is_quiet() {
+        return Ok(());
+    }
+
     let pop_id = format!("Population ID: {:02}, ", 0_usize);
     let pop_cnt = format!("Population Count: {:03}, ", 0_usize);
     let mutation = format!("Mutation: {:01}, ", 0_usize);
@@ -77,7 +95,11 @@ pub fn initial_dsp() -> io::Result<()> {
     let climb_cnt = format!("Climbers: {:02}, ", 0_usize);
     let k_temp = format!("K Temp: {:07.04}, ", 0_f64);
     let score_decay = format!("Decay: {:05.03}, ", 0_f64);
-    let avg_climb_iter = format!("Avg. Climb Iter: {:09.02}", 0_f64);
+    let step_size = format!("Step: {:05.03}, ", 0_f64);
+    let avg_climb_iter = format!("Avg. Climb Iter: {:09.02}, ", 0_f64);
+    let selection = format!("Selection: {:<14}, ", "--");
+    let local_search = format!("Local Search: {:<18}, ", "--");
+    let swap_policy = format!("Swap Policy: {:<13}", "--");
 
     stdout().queue(Clear(ClearType::All))?;
 
@@ -85,27 +107,62 @@ pub fn initial_dsp() -> io::Result<()> {
     stdout().queue(Print("-- Best Population --"))?;
     stdout().queue(MoveTo(0, POP_STATS_Y))?;
     stdout().queue(Print(format!(
-        "{}{}{}{}{}{}{}{}",
-        pop_id, pop_cnt, mutation, elite_cnt, climb_cnt, k_temp, score_decay, avg_climb_iter
+        "{}{}{}{}{}{}{}{}{}{}{}{}",
+        pop_id,
+        pop_cnt,
+        mutation,
+        elite_cnt,
+        climb_cnt,
+        k_temp,
+        score_decay,
+        step_size,
+        avg_climb_iter,
+        selection,
+        local_search,
+        swap_policy
     )))?;
     // stdout().queue(MoveTo(0, AVG_Y))?;
     // stdout().queue(Print(format!("{} --", AVG_NAME,)))?;
 
+    // The layout is scored for the hand position the mods describe, so they are shown with it
+    let mods = &get_config().fingers.mods;
+    let kb_header = if mods.is_empty() {
+        "-- Best Keyboard --".to_string()
+    } else {
+        let mod_names: Vec<String> = mods.iter().map(ToString::to_string).collect();
+        format!("-- Best Keyboard ({} mod) --", mod_names.join(", "))
+    };
+
     stdout().queue(MoveTo(0, KB_HEADER_Y))?;
-    stdout().queue(Print("-- Best Keyboard --"))?;
+    stdout().queue(Print(kb_header))?;
 
     stdout().queue(MoveTo(0, ITER_Y))?;
     stdout().queue(Print(format!("{}{:05}", ITER_NAME, 0_i32)))?;
+    stdout().queue(MoveTo(0, META_DIV_Y))?;
+    stdout().queue(Print(format!("{} --", META_DIV_NAME)))?;
 
     stdout().queue(MoveTo(0, CUR_POP_HEADER_Y))?;
     stdout().queue(Print("-- Current Population --"))?;
     stdout().queue(MoveTo(0, CUR_POP_STATS_Y))?;
     stdout().queue(Print(format!(
-        "{}{}{}{}{}{}{}{}",
-        pop_id, pop_cnt, mutation, elite_cnt, climb_cnt, k_temp, score_decay, avg_climb_iter
+        "{}{}{}{}{}{}{}{}{}{}{}{}",
+        pop_id,
+        pop_cnt,
+        mutation,
+        elite_cnt,
+        climb_cnt,
+        k_temp,
+        score_decay,
+        step_size,
+        avg_climb_iter,
+        selection,
+        local_search,
+        swap_policy
     )))?;
     stdout().queue(MoveTo(0, CUR_AVG_Y))?;
-    stdout().queue(Print(format!("{} --", CUR_AVG_NAME,)))?;
+    stdout().queue(Print(format!("{} --", CUR_AVG_NAME)))?;
+    stdout().queue(MoveTo(0, CUR_DIV_Y))?;
+    stdout().queue(Print(format!("{} --", CUR_DIV_NAME)))?;
     stdout().queue(MoveTo(0, EVAL_Y))?;
     stdout().queue(Print(format!("{} --", EVAL_NAME)))?;
     stdout().queue(MoveTo(0, CLIMB_HEADER_Y))?;
@@ -129,6 +186,10 @@ pub fn initial_dsp() -> io::Result<()> {
 }
 
 pub fn update_iter(iter: usize) -> io::Result<()> {
+    if is_quiet() {
+        return Ok(());
+    }
+
     stdout().queue(SavePosition)?;
     stdout().queue(MoveTo(ITER_NUM_X, ITER_Y))?;
     stdout().queue(Print(format!("{:05}", iter)))?;
@@ -140,6 +201,10 @@ pub fn update_iter(iter: usize) -> io::Result<()> {
 }
 
 pub fn update_best_pop_dsp(population: &Population) -> io::Result<()> {
+    if is_quiet() {
+        return Ok(());
+    }
+
     let pop_id = format!("Population ID: {:05}, ", population.get_id());
     let pop_cnt = format!("Population Count: {:03}, ", population.get_pop_cnt());
     let mutation = format!("Mutation: {:01}, ", population.get_mutation());
@@ -147,13 +212,40 @@ pub fn update_best_pop_dsp(population: &Population) -> io::Result<()> {
     let climb_cnt = format!("Climbers: {:02}, ", population.get_climb_cnt());
     let k_temp = format!("K Temp: {:08.04}, ", population.get_k_temp());
     let score_decay = format!("Decay: {:05.03}, ", population.get_score_decay());
-    let avg_climb_iter = format!("Avg. Climb Iter: {:09.02}", population.get_avg_climb_iter());
+    let step_size = format!("Step: {:05.03}, ", population.get_step_sizes().get_mean());
+    let avg_climb_iter = format!(
+        "Avg. Climb Iter: {:09.02}, ",
+        population.get_avg_climb_iter()
+    );
+    let selection = format!(
+        "Selection: {:<14}, ",
+        population.get_selection().to_string()
+    );
+    let local_search = format!(
+        "Local Search: {:<18}, ",
+        population.get_local_search().to_string()
+    );
+    let swap_policy = format!(
+        "Swap Policy: {:<13}",
+        population.get_swap_policy().to_string()
+    );
 
     stdout().queue(SavePosition)?;
     stdout().queue(MoveTo(0, POP_STATS_Y))?;
     stdout().queue(Print(format!(
-        "{}{}{}{}{}{}{}{}",
-        pop_id, pop_cnt, mutation, elite_cnt, climb_cnt, k_temp, score_decay, avg_climb_iter
+        "{}{}{}{}{}{}{}{}{}{}{}{}",
+        pop_id,
+        pop_cnt,
+        mutation,
+        elite_cnt,
+        climb_cnt,
+        k_temp,
+        score_decay,
+        step_size,
+        avg_climb_iter,
+        selection,
+        local_search,
+        swap_policy
     )))?;
     stdout().queue(RestorePosition)?;
 
@@ -163,6 +255,10 @@ pub fn update_best_pop_dsp(population: &Population) -> io::Result<()> {
 }
 
 pub fn update_cur_pop_dsp(population: &Population) -> io::Result<()> {
+    if is_quiet() {
+        return Ok(());
+    }
+
     let pop_id = format!("Population ID: {:05}, ", population.get_id());
     let pop_cnt = format!("Population Count: {:03}, ", population.get_pop_cnt());
     let mutation = format!("Mutation: {:01}, ", population.get_mutation());
@@ -170,13 +266,40 @@ pub fn update_cur_pop_dsp(population: &Population) -> io::Result<()> {
     let climb_cnt = format!("Climbers: {:02}, ", population.get_climb_cnt());
     let k_temp = format!("K Temp: {:08.04}, ", population.get_k_temp());
     let score_decay = format!("Decay: {:05.03}, ", population.get_score_decay());
-    let avg_climb_iter = format!("Avg. Climb Iter: {:09.02}", population.get_avg_climb_iter());
+    let step_size = format!("Step: {:05.03}, ", population.get_step_sizes().get_mean());
+    let avg_climb_iter = format!(
+        "Avg. Climb Iter: {:09.02}, ",
+        population.get_avg_climb_iter()
+    );
+    let selection = format!(
+        "Selection: {:<14}, ",
+        population.get_selection().to_string()
+    );
+    let local_search = format!(
+        "Local Search: {:<18}, ",
+        population.get_local_search().to_string()
+    );
+    let swap_policy = format!(
+        "Swap Policy: {:<13}",
+        population.get_swap_policy().to_string()
+    );
 
     stdout().queue(SavePosition)?;
     stdout().queue(MoveTo(0, CUR_POP_STATS_Y))?;
     stdout().queue(Print(format!(
-        "{}{}{}{}{}{}{}{}",
-        pop_id, pop_cnt, mutation, elite_cnt, climb_cnt, k_temp, score_decay, avg_climb_iter
+        "{}{}{}{}{}{}{}{}{}{}{}{}",
+        pop_id,
+        pop_cnt,
+        mutation,
+        elite_cnt,
+        climb_cnt,
+        k_temp,
+        score_decay,
+        step_size,
+        avg_climb_iter,
+        selection,
+        local_search,
+        swap_policy
     )))?;
     stdout().queue(RestorePosition)?;
 
@@ -186,6 +309,10 @@ pub fn update_cur_pop_dsp(population: &Population) -> io::Result<()> {
 }
 
 pub fn update_qwerty(score: f64) -> io::Result<()> {
+    if is_quiet() {
+        return Ok(());
+    }
+
     stdout().queue(SavePosition)?;
     stdout().queue(MoveTo(QWERTY_NUM_X, QWERTY_Y))?;
     stdout().queue(Print(format!("{:05}", score)))?;
@@ -197,6 +324,10 @@ pub fn update_qwerty(score: f64) -> io::Result<()> {
 }
 
 pub fn update_dvorak(score: f64) -> io::Result<()> {
+    if is_quiet() {
+        return Ok(());
+    }
+
     stdout().queue(SavePosition)?;
     stdout().queue(MoveTo(DVORAK_NUM_X, DVORAK_Y))?;
     stdout().queue(Print(format!("{:05}", score)))?;
@@ -219,6 +350,10 @@ pub fn update_dvorak(score: f64) -> io::Result<()> {
 // }
 
 pub fn update_cur_avg(score: f64) -> io::Result<()> {
+    if is_quiet() {
+        return Ok(());
+    }
+
     stdout().queue(SavePosition)?;
     stdout().queue(MoveTo(CUR_AVG_NUM_X, CUR_AVG_Y))?;
     stdout().queue(Print(format!("{}", score)))?;
@@ -229,9 +364,53 @@ pub fn update_cur_avg(score: f64) -> io::Result<()> {
     return Ok(());
 }
 
+pub fn update_meta_diversity(stats: &DiversityStats) -> io::Result<()> {
+    if is_quiet() {
+        return Ok(());
+    }
+
+    stdout().queue(SavePosition)?;
+    stdout().queue(MoveTo(META_DIV_NUM_X, META_DIV_Y))?;
+    stdout().queue(Print(format_diversity(stats)))?;
+    stdout().queue(RestorePosition)?;
+
+    stdout().flush()?;
+
+    return Ok(());
+}
+
+pub fn update_cur_diversity(stats: &DiversityStats) -> io::Result<()> {
+    if is_quiet() {
+        return Ok(());
+    }
+
+    stdout().queue(SavePosition)?;
+    stdout().queue(MoveTo(CUR_DIV_NUM_X, CUR_DIV_Y))?;
+    stdout().queue(Print(format_diversity(stats)))?;
+    stdout().queue(RestorePosition)?;
+
+    stdout().flush()?;
+
+    return Ok(());
+}
+
+fn format_diversity(stats: &DiversityStats) -> String {
+    return format!(
+        "Mean Slots: {:05.02}, Min: {:02}, Max: {:02}, Weighted Displacement: {:06.04}   ",
+        stats.get_mean(),
+        stats.get_min(),
+        stats.get_max(),
+        stats.get_weighted_mean()
+    );
+}
+
 // At least for now, it would be more contrived to iterate through everything
 // FUTURE: This is not a good long term solution though
 pub fn update_best_kb(kb: &Keyboard) -> io::Result<()> {
+    if is_quiet() {
+        return Ok(());
+    }
+
     let info: String = format!(
         "Generation: {:05}, ID: {:07}, Score: {:18}, Positive Iterations: {:05}",
         kb.get_generation(),
@@ -263,6 +442,10 @@ pub fn update_best_kb(kb: &Keyboard) -> io::Result<()> {
 }
 
 pub fn update_climb_info(info: &str) -> io::Result<()> {
+    if is_quiet() {
+        return Ok(());
+    }
+
     stdout().queue(SavePosition)?;
     stdout().queue(MoveTo(0, CLIMB_INFO_Y))?;
     stdout().queue(Print(info))?;
@@ -274,6 +457,10 @@ pub fn update_climb_info(info: &str) -> io::Result<()> {
 }
 
 pub fn update_eval_dsp(num: usize) -> io::Result<()> {
+    if is_quiet() {
+        return Ok(());
+    }
+
     let to_print = if num > 0 {
         format!("{:03}", num)
     } else {
@@ -289,3 +476,11 @@ pub fn update_eval_dsp(num: usize) -> io::Result<()> {
 
     return Ok(());
 }
+
+pub fn set_quiet() {
+    let _ = QUIET.set(true);
+}
+
+fn is_quiet() -> bool {
+    return QUIET.get().copied().unwrap_or(false);
+}
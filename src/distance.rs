@@ -0,0 +1,61 @@
+use crate::{
+    config::get_config,
+    eval_funcs::{StaggerModel, get_stagger, is_same_finger},
+    keyboard::{Finger, THUMB_ROW, get_col_pos, get_finger_id},
+    structs::Slot,
+};
+
+// How far each row is shifted right on a row-staggered board, in key widths, from the number row
+// down. Thumb keys sit level with the bottom row
+const ROW_STAGGER: [f64; 5] = [0.0, 0.5, 0.75, 1.25, 1.25];
+
+// Where a slot sits on the board, in key widths. x grows to the right and y grows downward. Layer
+// slots sit where their physical key is, and the ISO key sits left of the bottom row
+pub fn get_coords(slot: Slot) -> (f64, f64) {
+    let phys = slot.get_phys();
+    let row = phys.get_row();
+    let col = phys.get_col();
+    let x = get_col_pos(phys);
+
+    return match get_stagger() {
+        StaggerModel::Row => (x + ROW_STAGGER[row], row as f64),
+        StaggerModel::Ortholinear => (x, row as f64),
+        StaggerModel::Column => {
+            if row == THUMB_ROW {
+                return (x, row as f64);
+            }
+
+            let offsets = &get_config().geometry.column_offsets;
+            let offset = offsets[col.min(offsets.len() - 1)];
+            (x, row as f64 - offset)
+        }
+    };
+}
+
+// Efficiency from finger travel alone, on the same scale as the rule-based score. A finger
+// travels from its home key unless it typed the last key, in which case it starts there and a
+// sideways move is charged again as lateral stretch
+pub fn get_distance_eff(this_slot: Slot, last_slot: Option<Slot>) -> f64 {
+    let config = &get_config().effort;
+
+    let (this_x, this_y) = get_coords(this_slot);
+    let mut lateral = 0.0_f64;
+    let start = match last_slot {
+        Some(last) if last == this_slot => this_slot,
+        Some(last) if is_same_finger(this_slot, last) => {
+            lateral = (this_x - get_coords(last).0).abs();
+            last
+        }
+        _ => Finger::get_home_slot(this_slot),
+    };
+
+    let (start_x, start_y) = get_coords(start);
+    let travel = (this_x - start_x).hypot(this_y - start_y);
+    let effort = (travel + config.lateral_weight * lateral) / get_strength(this_slot);
+
+    return 1.0_f64 / config.distance_scale.mul_add(effort, 1.0_f64);
+}
+
+fn get_strength(slot: Slot) -> f64 {
+    return get_config().fingers.strength[get_finger_id(slot).get_index()];
+}
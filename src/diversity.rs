@@ -0,0 +1,92 @@
+use crate::{config::get_config, keyboard::Keyboard};
+
+// Pairwise distances across a group of keyboards. mean/min/max are counted in differing slots,
+// weighted_mean in frequency-weighted key displacement
+#[derive(Debug, Clone, Copy)]
+pub struct DiversityStats {
+    mean: f64,
+    min: usize,
+    max: usize,
+    weighted_mean: f64,
+}
+
+impl DiversityStats {
+    pub fn from_keyboards(kbs: &[&Keyboard]) -> Self {
+        let mut total_dist: usize = 0;
+        let mut total_weighted: f64 = 0.0;
+        let mut min = usize::MAX;
+        let mut max: usize = 0;
+        let mut pairs: usize = 0;
+
+        for (i, kb_a) in kbs.iter().enumerate() {
+            for kb_b in kbs.iter().skip(i + 1) {
+                let dist = kb_a.distance(kb_b);
+                total_dist += dist;
+                total_weighted += kb_a.weighted_distance(kb_b);
+                min = min.min(dist);
+                max = max.max(dist);
+                pairs += 1;
+            }
+        }
+
+        if pairs == 0 {
+            return Self {
+                mean: 0.0,
+                min: 0,
+                max: 0,
+                weighted_mean: 0.0,
+            };
+        }
+
+        return Self {
+            mean: total_dist as f64 / pairs as f64,
+            min,
+            max,
+            weighted_mean: total_weighted / pairs as f64,
+        };
+    }
+
+    pub fn get_mean(&self) -> f64 {
+        return self.mean;
+    }
+
+    pub fn get_min(&self) -> usize {
+        return self.min;
+    }
+
+    pub fn get_max(&self) -> usize {
+        return self.max;
+    }
+
+    pub fn get_weighted_mean(&self) -> f64 {
+        return self.weighted_mean;
+    }
+}
+
+// Scores used when picking keyboards by roulette. Without a sharing radius these are the raw
+// scores. With one, each score is divided by its niche count so that crowded regions of the
+// search space are picked less often (Goldberg & Richardson fitness sharing)
+// PERF: Every pair is compared, so this is quadratic in the population size
+pub fn get_selection_fitness(kbs: &[Keyboard]) -> Vec<f64> {
+    let config = &get_config().diversity;
+    let Some(radius) = config.sharing_radius else {
+        return kbs.iter().map(|kb| return kb.get_score()).collect();
+    };
+
+    return kbs
+        .iter()
+        .map(|kb_a| {
+            let niche_cnt = kbs.iter().fold(0.0_f64, |acc, kb_b| {
+                let dist = kb_a.distance(kb_b) as f64;
+                if dist >= radius {
+                    return acc;
+                }
+
+                return acc + 1.0 - (dist / radius).powf(config.sharing_alpha);
+            });
+
+            // A keyboard is always within its own niche, so the count is at least one
+            return kb_a.get_score() / niche_cnt.max(1.0);
+        })
+        .collect();
+}
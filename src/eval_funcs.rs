@@ -1,10 +1,19 @@
-use core::cmp;
+use core::{cmp, fmt, str::FromStr};
+
+use anyhow::{Error, anyhow};
 
 use crate::{
-    base_eff, edge_cols, home_row,
-    keyboard::{Finger, Hand, KeyCompare},
+    base_eff,
+    config::{DEFAULT_STRENGTH, get_config},
+    distance::get_distance_eff,
+    edge_cols, home_row,
+    keyboard::{
+        ErgoMod, Finger, FingerId, Hand, KeyCompare, get_col_dist, get_finger_id, get_rule_col,
+        has_mod,
+    },
     most_rows, obscure_cols, scoring,
     structs::Slot,
+    thumb_row,
 };
 
 scoring!();
@@ -13,10 +22,111 @@ home_row!();
 // cols!();
 edge_cols!();
 obscure_cols!();
+thumb_row!();
+
+// The physical board being scored. The original rules assume a row-staggered board, where each
+// row is shifted right of the one above it. That slope makes some reaches harder for the left
+// hand than the mirror-image reach on the right
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StaggerModel {
+    Row,
+    // Keys in a straight grid. Both hands are treated the same
+    Ortholinear,
+    // Columns shifted vertically to follow finger length. Both hands are treated the same, and
+    // vertical reaches between neighbouring columns account for geometry.column_offsets
+    Column,
+}
+
+impl fmt::Display for StaggerModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            StaggerModel::Row => write!(f, "Row"),
+            StaggerModel::Ortholinear => write!(f, "Ortholinear"),
+            StaggerModel::Column => write!(f, "Column"),
+        };
+    }
+}
+
+impl FromStr for StaggerModel {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "row" => Ok(StaggerModel::Row),
+            "ortholinear" | "ortho" => Ok(StaggerModel::Ortholinear),
+            "column" | "columnar" => Ok(StaggerModel::Column),
+            _ => Err(anyhow!("expected row, ortholinear, or column")),
+        };
+    }
+}
+
+pub fn get_stagger() -> StaggerModel {
+    return get_config().geometry.stagger;
+}
+
+// Switching to a layer is charged on the first key typed there. Staying on the layer costs less,
+// since the layer key is already held
+pub fn get_layer_mult(layer: usize, last_layer: usize) -> f64 {
+    if layer == 0 {
+        return BASE_EFF;
+    }
+
+    let config = &get_config().layer;
+    if layer == last_layer {
+        return config.hold_cost;
+    }
+
+    return config.switch_cost;
+}
+
+// Blends the rule-based score with the distance-based one by effort.distance_weight
+pub fn get_key_eff(this_slot: Slot, last_slot: Option<Slot>, prev_slot: Option<Slot>) -> f64 {
+    let rule_eff = get_rule_eff(this_slot, last_slot, prev_slot);
+
+    let weight = get_config().effort.distance_weight;
+    if weight <= 0.0_f64 {
+        return rule_eff;
+    }
+
+    let distance_eff = get_distance_eff(this_slot, last_slot);
+
+    return rule_eff.mul_add(1.0_f64 - weight, distance_eff * weight);
+}
+
+// NOTE: A single major efficiency penalty at any point in the algorithm can cause the entire
+// layout to change. Be careful over-indexing for any particular factor
+fn get_rule_eff(this_slot: Slot, last_slot: Option<Slot>, prev_slot: Option<Slot>) -> f64 {
+    let mut eff = BASE_EFF;
+
+    eff *= global_adjustments(this_slot);
+
+    let last_compare: Option<KeyCompare> =
+        last_slot.map(|last| return compare_slots(this_slot, last, true));
+    if let Some(key_compare) = last_compare {
+        match key_compare {
+            KeyCompare::Mult(x) => return eff * x,
+            KeyCompare::Mismatch => {}
+        }
+    }
+
+    let prev_compare: Option<KeyCompare> =
+        prev_slot.map(|prev| return compare_slots(this_slot, prev, false));
+    if let Some(key_compare) = prev_compare {
+        match key_compare {
+            KeyCompare::Mult(x) => return eff * x,
+            KeyCompare::Mismatch => {}
+        }
+    }
+
+    eff *= check_key_no_hist(this_slot);
+
+    return eff;
+}
 
 pub fn global_adjustments(slot: Slot) -> f64 {
-    let mut mult = BASE_EFF;
-    let finger = Finger::from_slot(slot);
+    let finger_id = get_finger_id(slot);
+    let mut mult = get_strength_mult(finger_id);
+    let finger = finger_id.get_finger();
     let row = slot.get_row();
 
     // The algo doesn't intrinsically know these fingers are less dexterous
@@ -31,6 +141,35 @@ pub fn global_adjustments(slot: Slot) -> f64 {
     return mult;
 }
 
+// Fingers set weaker than their default strength lose efficiency on every key, in proportion.
+// Stronger fingers are not rewarded, since the rules already assume a healthy hand
+fn get_strength_mult(finger_id: FingerId) -> f64 {
+    let i = finger_id.get_index();
+    let strength = get_config().fingers.strength[i];
+
+    return (strength / DEFAULT_STRENGTH[i]).min(BASE_EFF);
+}
+
+// Scales the score by how far each finger's share of the keystrokes is from its target share. The
+// deviation is half the summed absolute difference, from 0 for an exact match to 1
+pub fn get_load_mult(finger_uses: &[f64], targets: &[f64]) -> f64 {
+    let total_uses = finger_uses.iter().fold(0.0_f64, |acc, u| return acc + u);
+    let total_target = targets.iter().fold(0.0_f64, |acc, t| return acc + t);
+    if total_uses <= 0.0_f64 || total_target <= 0.0_f64 {
+        return BASE_EFF;
+    }
+
+    let deviation = finger_uses
+        .iter()
+        .zip(targets)
+        .fold(0.0_f64, |acc, (u, t)| {
+            return acc + (u / total_uses - t / total_target).abs();
+        })
+        / 2.0_f64;
+
+    return BASE_EFF - get_config().fingers.load_weight * deviation;
+}
+
 pub fn compare_slots(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> KeyCompare {
     let this_hand = Hand::from_slot(this_slot);
     let last_hand = Hand::from_slot(last_slot);
@@ -38,6 +177,12 @@ pub fn compare_slots(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> KeyCo
         return KeyCompare::Mismatch;
     }
 
+    if Finger::from_slot(this_slot) == Finger::Thumb
+        || Finger::from_slot(last_slot) == Finger::Thumb
+    {
+        return compare_thumb(this_slot, last_slot, is_bigram);
+    }
+
     let mut mult = BASE_EFF;
     mult *= check_index_ext(this_slot, last_slot, is_bigram);
     mult *= check_pinky_ext(this_slot, last_slot, is_bigram);
@@ -75,6 +220,114 @@ pub fn compare_slots(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> KeyCo
     return KeyCompare::Mult(mult);
 }
 
+// The thumb moves independently of the fingers. A finger key typed after a thumb key is a
+// Mismatch, so it is scored against the key before the thumb press. With space on a thumb, the
+// last letter of one word and the first letter of the next are scored as a skipgram instead of
+// the history being reset. A thumb press straight after a finger on the same hand costs a little,
+// since the hand has to coordinate both
+fn compare_thumb(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> KeyCompare {
+    if Finger::from_slot(this_slot) != Finger::Thumb {
+        return KeyCompare::Mismatch;
+    }
+
+    if Finger::from_slot(last_slot) == Finger::Thumb {
+        if this_slot == last_slot {
+            return KeyCompare::Mult(BASE_EFF);
+        }
+
+        return KeyCompare::Mult(get_base_sf_penalty(is_bigram));
+    }
+
+    if is_bigram {
+        return KeyCompare::Mult(D_LO_B);
+    }
+
+    return KeyCompare::Mult(BASE_EFF);
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BigramKind {
+    Repeat,
+    SameFinger,
+    Scissor,
+    SameHandRow,
+    SameHandJump,
+    Thumb,
+    Alternate,
+}
+
+impl BigramKind {
+    pub fn get_name(self) -> &'static str {
+        return match self {
+            BigramKind::Repeat => "Repeated key",
+            BigramKind::SameFinger => "Same finger",
+            BigramKind::Scissor => "Scissor",
+            BigramKind::SameHandRow => "Same hand, same row",
+            BigramKind::SameHandJump => "Same hand, row change",
+            BigramKind::Thumb => "Thumb and finger, same hand",
+            BigramKind::Alternate => "Hand alternation",
+        };
+    }
+}
+
+// Buckets a bigram by which branch of compare_slots scores it
+pub fn classify_bigram(this_slot: Slot, last_slot: Slot) -> BigramKind {
+    if this_slot == last_slot {
+        return BigramKind::Repeat;
+    }
+
+    if Hand::from_slot(this_slot) != Hand::from_slot(last_slot) {
+        return BigramKind::Alternate;
+    }
+
+    if is_same_finger(this_slot, last_slot) {
+        return BigramKind::SameFinger;
+    }
+
+    if Finger::from_slot(this_slot) == Finger::Thumb
+        || Finger::from_slot(last_slot) == Finger::Thumb
+    {
+        return BigramKind::Thumb;
+    }
+
+    if is_scissor(this_slot, last_slot) {
+        return BigramKind::Scissor;
+    }
+
+    if this_slot.get_row() == last_slot.get_row() {
+        return BigramKind::SameHandRow;
+    }
+
+    return BigramKind::SameHandJump;
+}
+
+// Mirrors the same-finger branch of compare_slots. Repeating the same key is not counted
+pub fn is_same_finger(this_slot: Slot, last_slot: Slot) -> bool {
+    if this_slot == last_slot || Hand::from_slot(this_slot) != Hand::from_slot(last_slot) {
+        return false;
+    }
+
+    return Finger::from_slot(this_slot) == Finger::from_slot(last_slot);
+}
+
+// Mirrors the cases check_scissor penalizes
+pub fn is_scissor(this_slot: Slot, last_slot: Slot) -> bool {
+    let this_finger = Finger::from_slot(this_slot);
+    let last_finger = Finger::from_slot(last_slot);
+    if Hand::from_slot(this_slot) != Hand::from_slot(last_slot)
+        || this_finger == last_finger
+        || this_finger == Finger::Thumb
+        || last_finger == Finger::Thumb
+    {
+        return false;
+    }
+
+    let col_diff = get_col_dist(this_slot, last_slot);
+    let row_diff = get_vertical_dist(this_slot, last_slot);
+
+    return col_diff == 1 && row_diff >= 2;
+}
+
 fn get_row_mult(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> f64 {
     let this_hand = Hand::from_slot(this_slot);
     let last_hand = Hand::from_slot(last_slot);
@@ -86,16 +339,20 @@ fn get_row_mult(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> f64 {
         last_slot.get_col()
     );
 
+    let this_row = this_slot.get_row();
+    let last_row = last_slot.get_row();
+
     let mut mult = BASE_EFF;
-    // The slope of the keys works against the left hand
-    if this_hand == Hand::Left && is_bigram {
+    // On a row stagger, the slope of the keys works against the left hand. The angle mod turns
+    // the wrist to follow the slope down to the bottom row
+    let angled = has_mod(ErgoMod::Angle) && (this_row == BOT_ROW || last_row == BOT_ROW);
+    let sloped = get_stagger() == StaggerModel::Row && this_hand == Hand::Left && !angled;
+    if sloped && is_bigram {
         mult *= D_LO_B;
-    } else if this_hand == Hand::Left && !is_bigram {
+    } else if sloped && !is_bigram {
         mult *= D_LO_S;
     }
 
-    let this_row = this_slot.get_row();
-    let last_row = last_slot.get_row();
     debug_assert_ne!(
         this_row, last_row,
         "Rows {this_row} and {last_row} are the same in get_row_mult"
@@ -114,6 +371,15 @@ fn get_row_mult(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> f64 {
 }
 
 fn check_index_ext(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> f64 {
+    return match get_stagger() {
+        StaggerModel::Row => check_index_ext_row(this_slot, last_slot, is_bigram),
+        StaggerModel::Ortholinear | StaggerModel::Column => {
+            check_index_ext_grid(this_slot, last_slot, is_bigram)
+        }
+    };
+}
+
+fn check_index_ext_row(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> f64 {
     debug_assert_eq!(
         Hand::from_slot(this_slot),
         Hand::from_slot(last_slot),
@@ -124,9 +390,9 @@ fn check_index_ext(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> f64 {
 
     return match (
         this_slot.get_row(),
-        this_slot.get_col(),
+        get_rule_col(this_slot),
         last_slot.get_row(),
-        last_slot.get_col(),
+        get_rule_col(last_slot),
         is_bigram,
     ) {
         // T and 5 (Not penalized. No more movement than hitting R and 4)
@@ -157,6 +423,38 @@ fn check_index_ext(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> f64 {
     };
 }
 
+// Without a row stagger, both index extension columns are straight above and below the home row,
+// so the same reach costs the same on either hand. G and H are a sideways reach, and the keys
+// above and below them add a diagonal one
+fn check_index_ext_grid(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> f64 {
+    debug_assert_eq!(
+        Hand::from_slot(this_slot),
+        Hand::from_slot(last_slot),
+        "Cols {} and {} are on different hands",
+        this_slot.get_col(),
+        last_slot.get_col()
+    );
+
+    let get_mult = |slot: Slot| -> f64 {
+        let col = get_rule_col(slot);
+        if col != L_EXT && col != R_EXT {
+            return BASE_EFF;
+        }
+
+        return match (slot.get_row(), is_bigram) {
+            (HOME_ROW, true) => D_LO_B,
+            (HOME_ROW, false) => D_LO_S,
+            (TOP_ROW | BOT_ROW, true) => D_ME_B,
+            (TOP_ROW | BOT_ROW, false) => D_ME_S,
+            (_, true) => D_HI_B,
+            (_, false) => D_HI_S,
+        };
+    };
+
+    // When both keys are on an extension column, only the harder reach is charged
+    return get_mult(this_slot).min(get_mult(last_slot));
+}
+
 fn check_pinky_ext(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> f64 {
     debug_assert_eq!(
         Hand::from_slot(this_slot),
@@ -168,9 +466,9 @@ fn check_pinky_ext(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> f64 {
 
     return match (
         this_slot.get_row(),
-        this_slot.get_col(),
+        get_rule_col(this_slot),
         last_slot.get_row(),
-        last_slot.get_col(),
+        get_rule_col(last_slot),
         is_bigram,
     ) {
         // '
@@ -188,15 +486,15 @@ fn check_pinky_ext(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> f64 {
         // ]
         (TOP_ROW, R_NETHER, _, _, true) | (_, _, TOP_ROW, R_NETHER, true) => D_HI_B,
         (TOP_ROW, R_NETHER, _, _, false) | (_, _, TOP_ROW, R_NETHER, false) => D_HI_S,
-        // -/= or |
+        // -/=, |, or the ISO \n
         (NUM_ROW, R_SYMBOL | R_NETHER, _, _, true)
         | (_, _, NUM_ROW, R_SYMBOL | R_NETHER, true)
-        | (TOP_ROW, R_PIPE, _, _, true)
-        | (_, _, TOP_ROW, R_PIPE, true) => D_BU_B,
+        | (TOP_ROW | HOME_ROW, R_PIPE, _, _, true)
+        | (_, _, TOP_ROW | HOME_ROW, R_PIPE, true) => D_BU_B,
         (NUM_ROW, R_SYMBOL | R_NETHER, _, _, false)
         | (_, _, NUM_ROW, R_SYMBOL | R_NETHER, false)
-        | (TOP_ROW, R_PIPE, _, _, false)
-        | (_, _, TOP_ROW, R_PIPE, false) => D_BU_S,
+        | (TOP_ROW | HOME_ROW, R_PIPE, _, _, false)
+        | (_, _, TOP_ROW | HOME_ROW, R_PIPE, false) => D_BU_S,
         _ => 1.0,
     };
 }
@@ -265,7 +563,7 @@ fn get_col_sf_penalty(this_slot: Slot, last_slot: Slot, last: bool) -> f64 {
         "ERROR: Cols {this_col} and {last_col} are on different fingers when getting SF penalty",
     );
 
-    let col_diff = this_col.abs_diff(last_col);
+    let col_diff = get_col_dist(this_slot, last_slot);
     return match (col_diff, last) {
         (1, true) => D_ME_B,
         (2, true) => D_HI_B,
@@ -278,25 +576,24 @@ fn get_col_sf_penalty(this_slot: Slot, last_slot: Slot, last: bool) -> f64 {
 }
 
 fn get_center_dist(slot: Slot) -> usize {
+    let col = get_rule_col(slot);
     debug_assert!(
-        (L_PINKY..=R_PIPE).contains(&slot.get_col()),
-        "Col {} invalid in get_center_dist",
-        slot.get_col()
+        (L_PINKY..=R_PIPE).contains(&col),
+        "Col {col} invalid in get_center_dist",
     );
 
-    return if slot.get_col() <= L_EXT {
+    return if col <= L_EXT {
         L_EXT
-            .checked_sub(slot.get_col())
+            .checked_sub(col)
             .expect("{L_EXT} must be greater than {col}")
     } else {
-        slot.get_col()
-            .checked_sub(R_EXT)
+        col.checked_sub(R_EXT)
             .expect("{col} must be greater than {R_EXT}")
     };
 }
 
 /// # Panics
-/// Panics if the rows of each key are the same
+/// Panics if the rows of each key are the same.
 fn check_combo(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> f64 {
     let this_row = this_slot.get_row();
     let last_row = last_slot.get_row();
@@ -350,16 +647,26 @@ fn check_scissor(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> f64 {
         "{this_col} and {last_col} are the same in check_scissor"
     );
 
-    if this_col.abs_diff(last_col) > 1 {
+    if get_col_dist(this_slot, last_slot) > 1 {
         return 1.0;
     }
 
     let this_row = this_slot.get_row();
     let last_row = last_slot.get_row();
 
-    debug_assert_ne!(this_row, last_row, "Same rows when checking for scissor",);
+    debug_assert_ne!(this_row, last_row, "Same rows when checking for scissor");
 
     let hand = Hand::from_slot(this_slot);
+    if get_stagger() != StaggerModel::Row {
+        return match (get_vertical_dist(this_slot, last_slot), is_bigram) {
+            (2, true) => D_ME_B,
+            (2, false) => D_ME_S,
+            (3.., true) => D_HI_B,
+            (3.., false) => D_HI_S,
+            _ => 1.0,
+        };
+    }
+
     // Left-handed scissors are penalized beyond the base left-hand movement deduction because,
     // unlike right-handed scissors, you have to actually rock your hand to hit them
     return match (this_row.abs_diff(last_row), hand, is_bigram) {
@@ -373,9 +680,36 @@ fn check_scissor(this_slot: Slot, last_slot: Slot, is_bigram: bool) -> f64 {
     };
 }
 
+// Vertical distance between two keys in rows, rounded to the nearest row. Only column stagger
+// moves keys off their row. A column offset is how far that column sits above a straight grid
+fn get_vertical_dist(this_slot: Slot, last_slot: Slot) -> usize {
+    if get_stagger() != StaggerModel::Column {
+        return this_slot.get_row().abs_diff(last_slot.get_row());
+    }
+
+    let get_height = |slot: Slot| -> f64 {
+        let offsets = &get_config().geometry.column_offsets;
+        let offset = offsets[slot.get_col().min(offsets.len() - 1)];
+        return slot.get_row() as f64 - offset;
+    };
+
+    return (get_height(this_slot) - get_height(last_slot))
+        .abs()
+        .round() as usize;
+}
+
 pub fn check_key_no_hist(slot: Slot) -> f64 {
     let mut mult = BASE_EFF;
 
+    if slot.get_row() == THUMB_ROW {
+        let col = slot.get_col();
+        if col == L_THUMB_REACH || col == R_THUMB_REACH {
+            return mult * D_LO_B;
+        }
+
+        return mult;
+    }
+
     let row = slot.get_row();
     debug_assert!(
         (NUM_ROW..=BOT_ROW).contains(&slot.get_row()),
@@ -393,7 +727,7 @@ pub fn check_key_no_hist(slot: Slot) -> f64 {
         mult *= D_LO_B;
     }
 
-    let col = slot.get_col();
+    let col = get_rule_col(slot);
     if col == L_EXT || col == R_EXT {
         mult *= D_LO_B;
     } else if col == R_SYMBOL {
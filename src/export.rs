@@ -0,0 +1,411 @@
+use {
+    core::fmt::Write as _,
+    std::{fs, path::Path},
+};
+
+use anyhow::{Result, anyhow};
+
+use crate::{
+    config::get_config,
+    home_row,
+    keyboard::{
+        BOT_ROW, ISO_COL, Keyboard, NUM_ROW, TOP_ROW, ThumbPos, get_iso_slot, get_thumb_keys,
+        xkb_dead_keysym,
+    },
+    structs::{Key, Slot},
+};
+
+home_row!();
+
+// Thumb keys from left to right, as both firmwares list them
+const THUMB_ORDER: [ThumbPos; 4] = [
+    ThumbPos::LeftReach,
+    ThumbPos::Left,
+    ThumbPos::Right,
+    ThumbPos::RightReach,
+];
+
+// Kanata maps from the keys of the board it runs on. The alpha block keeps its Qwerty positions,
+// and the thumb keys come from the bottom row
+const KANATA_SRC: [&str; 4] = ["lmet", "lalt", "spc", "ralt"];
+
+// What a thumb position does on a layer
+enum ThumbAction {
+    Key(Key),
+    Layer(usize),
+    Held,
+    Transparent,
+    Empty,
+}
+
+// Writes a QMK keymap, a kanata config, or an XKB symbols file, depending on the extension of
+// `path`. QMK and kanata target a 34 key split board: the 3x10 alpha area plus the four thumb
+// keys. XKB targets a standard board
+pub fn export_layout(kb: &Keyboard, path: &Path) -> Result<()> {
+    let extension = path
+        .extension()
+        .and_then(|e| return e.to_str())
+        .map(str::to_lowercase);
+    let contents = match extension.as_deref() {
+        Some("c") => format_qmk(kb)?,
+        Some("kbd") => format_kanata(kb)?,
+        Some("xkb") => format_xkb(kb)?,
+        _ => {
+            return Err(anyhow!(
+                "Export path {} must end in .c (QMK), .kbd (kanata), or .xkb (XKB)",
+                path.display()
+            ));
+        }
+    };
+
+    fs::write(path, contents)?;
+
+    return Ok(());
+}
+
+fn check_split_layers() -> Result<()> {
+    if get_config().layer.count == 0 {
+        return Err(anyhow!(
+            "QMK and kanata exports need layer.count of at least 1. A 34 key board has no number \
+             row"
+        ));
+    }
+
+    return Ok(());
+}
+
+fn format_qmk(kb: &Keyboard) -> Result<String> {
+    check_split_layers()?;
+
+    let mut keymap = String::new();
+    let _ = writeln!(keymap, "// Generated by ma_keyboard");
+    let _ = writeln!(keymap, "#include QMK_KEYBOARD_H");
+    let _ = writeln!(keymap);
+    let _ = writeln!(
+        keymap,
+        "const uint16_t PROGMEM keymaps[][MATRIX_ROWS][MATRIX_COLS] = {{"
+    );
+
+    for layer in 0..=get_config().layer.count {
+        let mut codes: Vec<String> = Vec::new();
+        for slot in get_alpha_slots(layer) {
+            let key = kb.get_key_slots().get(&slot).copied();
+            codes.push(qmk_key(key)?);
+        }
+
+        for pos in THUMB_ORDER {
+            codes.push(match get_thumb_action(pos, layer) {
+                ThumbAction::Key(key) => qmk_key(Some(key))?,
+                ThumbAction::Layer(n) => format!("MO({n})"),
+                ThumbAction::Held | ThumbAction::Transparent => "_______".to_string(),
+                ThumbAction::Empty => "XXXXXXX".to_string(),
+            });
+        }
+
+        let _ = writeln!(keymap, "    [{layer}] = LAYOUT_split_3x5_2(");
+        for (i, row) in codes.chunks(10).enumerate() {
+            let sep = if i == 3 {
+                ""
+            } else {
+                ","
+            };
+            let _ = writeln!(keymap, "        {}{sep}", row.join(", "));
+        }
+        let _ = writeln!(keymap, "    ),");
+    }
+
+    let _ = writeln!(keymap, "}};");
+
+    return Ok(keymap);
+}
+
+fn format_kanata(kb: &Keyboard) -> Result<String> {
+    check_split_layers()?;
+
+    let layer_cnt = get_config().layer.count;
+
+    let mut config = String::new();
+    let _ = writeln!(config, ";; Generated by ma_keyboard");
+    let _ = writeln!(config, "(defsrc");
+    let _ = writeln!(config, "  q w e r t y u i o p");
+    let _ = writeln!(config, "  a s d f g h j k l ;");
+    let _ = writeln!(config, "  z x c v b n m , . /");
+    let _ = writeln!(config, "  {}", KANATA_SRC.join(" "));
+    let _ = writeln!(config, ")");
+    let _ = writeln!(config);
+
+    let _ = writeln!(config, "(defalias");
+    for layer in 1..=layer_cnt {
+        let _ = writeln!(config, "  l{layer} (layer-while-held layer{layer})");
+    }
+    let _ = writeln!(config, ")");
+
+    for layer in 0..=layer_cnt {
+        let mut names: Vec<String> = Vec::new();
+        for slot in get_alpha_slots(layer) {
+            let key = kb.get_key_slots().get(&slot).copied();
+            names.push(kanata_key(key)?);
+        }
+
+        for pos in THUMB_ORDER {
+            names.push(match get_thumb_action(pos, layer) {
+                ThumbAction::Key(key) => kanata_key(Some(key))?,
+                ThumbAction::Layer(n) => format!("@l{n}"),
+                ThumbAction::Held | ThumbAction::Transparent => "_".to_string(),
+                ThumbAction::Empty => "XX".to_string(),
+            });
+        }
+
+        let name = if layer == 0 {
+            "base".to_string()
+        } else {
+            format!("layer{layer}")
+        };
+
+        let _ = writeln!(config);
+        let _ = writeln!(config, "(deflayer {name}");
+        for row in names.chunks(10) {
+            let _ = writeln!(config, "  {}", row.join(" "));
+        }
+        let _ = writeln!(config, ")");
+    }
+
+    return Ok(config);
+}
+
+// Slots hold physical keys, so the symbols map straight onto the key codes. Finger reassignments
+// such as the angle and wide mods change how the board is typed, not which key sends what. Enter
+// keeps its usual place, and the rest of the board comes from the US layout
+fn format_xkb(kb: &Keyboard) -> Result<String> {
+    if get_config().layer.count > 0 {
+        return Err(anyhow!(
+            "XKB export covers the standard board only. Set layer.count to 0"
+        ));
+    }
+
+    let mut symbols = String::new();
+    let _ = writeln!(symbols, "// Generated by ma_keyboard");
+    let mods = &get_config().fingers.mods;
+    if !mods.is_empty() {
+        let mod_names: Vec<String> = mods.iter().map(ToString::to_string).collect();
+        let _ = writeln!(symbols, "// Optimized for the {} mod", mod_names.join(", "));
+    }
+
+    let _ = writeln!(symbols, "default partial alphanumeric_keys");
+    let _ = writeln!(symbols, "xkb_symbols \"basic\" {{");
+    let _ = writeln!(symbols, "    include \"us(basic)\"");
+    let _ = writeln!(symbols, "    name[Group1] = \"ma_keyboard\";");
+    let _ = writeln!(symbols);
+
+    for (slot, key) in kb.get_key_slots() {
+        if key.get_base() == '\n' || *key == Key::BLANK {
+            continue;
+        }
+
+        let Some(code) = get_xkb_code(*slot) else {
+            continue;
+        };
+
+        let _ = writeln!(
+            symbols,
+            "    key <{code}> {{ [ {}, {} ] }};",
+            xkb_key_sym(key.get_base())?,
+            xkb_key_sym(key.get_shift())?
+        );
+    }
+
+    let _ = writeln!(symbols, "}};");
+
+    // XKB leaves what a dead key composes to the Compose table. The common accents are already in
+    // the system one, and these lines cover the rest
+    let sequences = &get_config().dead_keys.sequences;
+    if !sequences.is_empty() {
+        let _ = writeln!(symbols);
+        let _ = writeln!(symbols, "// Dead key sequences, for ~/.XCompose:");
+        for seq in sequences {
+            let _ = writeln!(
+                symbols,
+                "// <{}> <{}> : \"{}\"",
+                xkb_dead_keysym(seq.get_dead())?,
+                xkb_keysym(seq.get_base())?,
+                escape_compose(seq.get_composed())
+            );
+        }
+    }
+
+    return Ok(symbols);
+}
+
+// Key code names from the XKB keycodes files. Rows are lettered from the bottom. On an ISO
+// board, backslash is the BKSL key left of Enter, and the ISO key is LSGT
+fn get_xkb_code(slot: Slot) -> Option<String> {
+    let row = slot.get_row();
+    let col = slot.get_col();
+
+    return match (row, col) {
+        (NUM_ROW, 0..=11) => Some(format!("AE{:02}", col + 1)),
+        (TOP_ROW, 0..=11) => Some(format!("AD{:02}", col + 1)),
+        (TOP_ROW, 12) => Some("BKSL".to_string()),
+        (HOME_ROW, 0..=10) => Some(format!("AC{:02}", col + 1)),
+        (HOME_ROW, 11) if get_iso_slot().is_some() => Some("BKSL".to_string()),
+        (BOT_ROW, 0..=9) => Some(format!("AB{:02}", col + 1)),
+        (BOT_ROW, ISO_COL) => Some("LSGT".to_string()),
+        _ => None,
+    };
+}
+
+// What a key sends. Dead keys send their dead keysym
+fn xkb_key_sym(c: char) -> Result<String> {
+    if get_config().dead_keys.is_dead(c) {
+        return xkb_dead_keysym(c);
+    }
+
+    return xkb_keysym(c);
+}
+
+// Quotes and backslashes end or escape an XCompose string
+fn escape_compose(c: char) -> String {
+    return match c {
+        '"' | '\\' => format!("\\{c}"),
+        _ => c.to_string(),
+    };
+}
+
+fn xkb_keysym(c: char) -> Result<String> {
+    let name = match c {
+        'a'..='z' | 'A'..='Z' | '0'..='9' => return Ok(c.to_string()),
+        ' ' => "space",
+        '!' => "exclam",
+        '"' => "quotedbl",
+        '#' => "numbersign",
+        '$' => "dollar",
+        '%' => "percent",
+        '&' => "ampersand",
+        '\'' => "apostrophe",
+        '(' => "parenleft",
+        ')' => "parenright",
+        '*' => "asterisk",
+        '+' => "plus",
+        ',' => "comma",
+        '-' => "minus",
+        '.' => "period",
+        '/' => "slash",
+        ':' => "colon",
+        ';' => "semicolon",
+        '<' => "less",
+        '=' => "equal",
+        '>' => "greater",
+        '?' => "question",
+        '@' => "at",
+        '[' => "bracketleft",
+        '\\' => "backslash",
+        ']' => "bracketright",
+        '^' => "asciicircum",
+        '_' => "underscore",
+        '`' => "grave",
+        '{' => "braceleft",
+        '|' => "bar",
+        '}' => "braceright",
+        '~' => "asciitilde",
+        // XKB names any Unicode character by its code point
+        _ if !c.is_ascii() => return Ok(format!("U{:04X}", u32::from(c))),
+        _ => return Err(anyhow!("No XKB keysym for \"{}\"", c)),
+    };
+
+    return Ok(name.to_string());
+}
+
+// The 3x10 block of a layer in reading order. Layer 0 is the base layer
+fn get_alpha_slots(layer: usize) -> Vec<Slot> {
+    let mut slots: Vec<Slot> = Vec::new();
+    for row in 1..=3 {
+        for col in 0..=9 {
+            if layer == 0 {
+                slots.push(Slot::from_tuple((row, col)));
+            } else {
+                slots.push(Slot::from_layer(layer, row, col));
+            }
+        }
+    }
+
+    return slots;
+}
+
+// Thumb keys work on every layer. On a layer, the key holding it stays held
+fn get_thumb_action(pos: ThumbPos, layer: usize) -> ThumbAction {
+    let config = get_config();
+    let access = &config.layer.access[..config.layer.count];
+
+    if let Some(i) = access.iter().position(|p| return *p == pos) {
+        if layer == 0 {
+            return ThumbAction::Layer(i + 1);
+        } else if layer == i + 1 {
+            return ThumbAction::Held;
+        }
+
+        return ThumbAction::Empty;
+    }
+
+    if let Some(key) = get_thumb_keys().get(&pos.get_slot()) {
+        if layer == 0 {
+            return ThumbAction::Key(*key);
+        }
+
+        return ThumbAction::Transparent;
+    }
+
+    return ThumbAction::Empty;
+}
+
+fn qmk_key(key: Option<Key>) -> Result<String> {
+    let Some(base) = key.map(Key::get_base) else {
+        return Ok("XXXXXXX".to_string());
+    };
+
+    let code = match base {
+        '\0' => "XXXXXXX".to_string(),
+        'a'..='z' | '0'..='9' => format!("KC_{}", base.to_ascii_uppercase()),
+        ',' => "KC_COMM".to_string(),
+        '.' => "KC_DOT".to_string(),
+        ';' => "KC_SCLN".to_string(),
+        '\'' => "KC_QUOT".to_string(),
+        '[' => "KC_LBRC".to_string(),
+        ']' => "KC_RBRC".to_string(),
+        '-' => "KC_MINS".to_string(),
+        '=' => "KC_EQL".to_string(),
+        '/' => "KC_SLSH".to_string(),
+        '\\' => "KC_BSLS".to_string(),
+        '`' => "KC_GRV".to_string(),
+        '\n' => "KC_ENT".to_string(),
+        ' ' => "KC_SPC".to_string(),
+        '\t' => "KC_TAB".to_string(),
+        // NOTE: Needs UNICODE_ENABLE in the keyboard's rules.mk. Only the base character is sent,
+        // so the shifted form is lost
+        _ if !base.is_ascii() => format!("UC(0x{:04X})", u32::from(base)),
+        _ => return Err(anyhow!("No QMK keycode for \"{}\"", base)),
+    };
+
+    return Ok(code);
+}
+
+fn kanata_key(key: Option<Key>) -> Result<String> {
+    let Some(base) = key.map(Key::get_base) else {
+        return Ok("XX".to_string());
+    };
+
+    let name = match base {
+        '\0' => "XX".to_string(),
+        '\n' => "ret".to_string(),
+        ' ' => "spc".to_string(),
+        '\t' => "tab".to_string(),
+        '`' => "grv".to_string(),
+        'a'..='z' | '0'..='9' | ',' | '.' | ';' | '\'' | '[' | ']' | '-' | '=' | '/' | '\\' => {
+            base.to_string()
+        }
+        // NOTE: Only the base character is typed, so the shifted form is lost
+        _ if !base.is_ascii() => format!("(unicode {base})"),
+        _ => return Err(anyhow!("No kanata key name for \"{}\"", base)),
+    };
+
+    return Ok(name);
+}
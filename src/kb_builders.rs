@@ -12,16 +12,21 @@ use {alloc::collections::BTreeMap, core::cmp};
 use rand::{Rng as _, rngs::SmallRng};
 
 use crate::{
-    cols, edge_cols, home_row, keys,
-    mapped_swap::select_key,
+    cols,
+    config::get_config,
+    edge_cols, home_row,
+    keyboard::{IsoKey, LAYER_ROW, get_iso_slot},
+    keys,
+    mapped_swap::{SwapPolicy, apply_confidence, select_key},
     most_cols, most_rows, obscure_cols,
     population::SwapTable,
     row_cnts, rows, static_keys,
     structs::{Key, Slot},
-    valid_locations,
+    thumb_row, valid_locations,
 };
 
 rows!();
+thumb_row!();
 valid_locations!();
 
 pub fn get_valid_key_locs_sorted() -> Vec<(Key, Vec<Slot>)> {
@@ -158,6 +163,27 @@ pub fn get_valid_key_locs_sorted() -> Vec<(Key, Vec<Slot>)> {
         ),
     ];
 
+    // With layers on, every key outside the alpha area can go anywhere on any layer. One blank is
+    // listed so the blanks filling the rest of the layers have valid slots too
+    if get_config().layer.count > 0 {
+        let layer_slots = get_layer_slots();
+        for (_, slots) in &mut key_locs {
+            if slots.iter().any(|s| return is_off_alpha(*s)) {
+                slots.clone_from(&layer_slots);
+            }
+        }
+
+        key_locs.push((Key::BLANK, layer_slots));
+    }
+
+    if get_iso_slot().is_some() {
+        add_iso_locs(&mut key_locs);
+    }
+
+    for (key, _) in &mut key_locs {
+        *key = apply_charset(*key);
+    }
+
     key_locs.sort_by(|a, b| {
         return a
             .1
@@ -250,12 +276,139 @@ fn make_slot_vec(input: &[(usize, usize)]) -> Vec<Slot> {
     return input.iter().map(|i| return Slot::from_tuple(*i)).collect();
 }
 
+// Every slot on the configured layers, layer by layer in reading order
+pub fn get_layer_slots() -> Vec<Slot> {
+    let mut slots: Vec<Slot> = Vec::new();
+    for layer in 1..=get_config().layer.count {
+        for row in TOP_ROW..=BOT_ROW {
+            for col in L_PINKY..=R_PINKY {
+                slots.push(Slot::from_layer(layer, row, col));
+            }
+        }
+    }
+
+    return slots;
+}
+
+// The number row and the pinky extension columns. With layers on, their keys move to a layer
+fn is_off_alpha(slot: Slot) -> bool {
+    return slot.get_row() == NUM_ROW || (slot.get_row() <= BOT_ROW && slot.get_col() > R_PINKY);
+}
+
+// On an ISO board, backslash sits left of Enter on the home row and Enter moves past it. A
+// swappable ISO key can hold any key allowed in the bottom left pinky slot, and the blank listed
+// for it can go anywhere those keys can. The ISO slot is listed first, so the blank fills it
+// when static keys are placed
+fn add_iso_locs(key_locs: &mut Vec<(Key, Vec<Slot>)>) {
+    let Some(iso_slot) = get_iso_slot() else {
+        return;
+    };
+
+    for (key, slots) in &mut *key_locs {
+        if *key == Key::from_tuple(BACKSLASH) {
+            *slots = vec![Slot::from_tuple((HOME_ROW, R_NETHER))];
+        } else if *key == Key::from_tuple(NEWLINE) {
+            *slots = vec![Slot::from_tuple((HOME_ROW, R_PIPE))];
+        }
+    }
+
+    match get_config().geometry.iso_key {
+        IsoKey::Swappable => {
+            let corner = Slot::from_tuple((BOT_ROW, L_PINKY));
+            let mut blank_slots = vec![iso_slot];
+            for (_, slots) in &mut *key_locs {
+                if !slots.contains(&corner) {
+                    continue;
+                }
+
+                slots.push(iso_slot);
+                for slot in slots.iter() {
+                    if !blank_slots.contains(slot) {
+                        blank_slots.push(*slot);
+                    }
+                }
+            }
+
+            key_locs.push((Key::BLANK, blank_slots));
+        }
+        IsoKey::Static(key) => key_locs.push((key, vec![iso_slot])),
+        IsoKey::Empty => {}
+    }
+}
+
+// The ISO slot when the optimizer can move keys into it
+pub fn get_iso_swap_slot() -> Option<Slot> {
+    if get_config().geometry.iso_key == IsoKey::Swappable {
+        return get_iso_slot();
+    }
+
+    return None;
+}
+
+// Fixed layouts like Qwerty are written for an ANSI board. On an ISO board, backslash and Enter
+// move to their ISO places, and the ISO key gets a blank or its static key
+pub fn move_to_iso(key_slots: &mut BTreeMap<Slot, Key>) {
+    let Some(iso_slot) = get_iso_slot() else {
+        return;
+    };
+
+    if let Some(backslash) = key_slots.remove(&Slot::from_tuple((TOP_ROW, R_PIPE))) {
+        let nether = Slot::from_tuple((HOME_ROW, R_NETHER));
+        if let Some(enter) = key_slots.insert(nether, backslash) {
+            key_slots.insert(Slot::from_tuple((HOME_ROW, R_PIPE)), enter);
+        }
+    }
+
+    let iso_key = match get_config().geometry.iso_key {
+        IsoKey::Swappable => Key::BLANK,
+        IsoKey::Static(key) => key,
+        IsoKey::Empty => return,
+    };
+
+    key_slots.entry(iso_slot).or_insert(iso_key);
+}
+
+// Layer slots left empty after placement get a blank
+pub fn fill_layer_blanks(key_slots: &mut BTreeMap<Slot, Key>) {
+    for slot in get_layer_slots() {
+        key_slots.entry(slot).or_insert(Key::BLANK);
+    }
+}
+
+// Fixed layouts like Qwerty are written without layers. When layers are on, their keys outside
+// the alpha area are moved onto the layers in reading order
+pub fn move_to_layers(key_slots: &mut BTreeMap<Slot, Key>) {
+    let has_layers = key_slots.keys().any(|s| return s.get_layer() > 0);
+    if get_config().layer.count == 0 || has_layers {
+        return;
+    }
+
+    let off_alpha: Vec<Slot> = key_slots
+        .keys()
+        .copied()
+        .filter(|s| return is_off_alpha(*s))
+        .collect();
+
+    for (slot, layer_slot) in off_alpha.into_iter().zip(get_layer_slots()) {
+        if let Some(key) = key_slots.remove(&slot) {
+            key_slots.insert(layer_slot, key);
+        }
+    }
+
+    fill_layer_blanks(key_slots);
+}
+
 pub fn check_col(row: usize, col: usize) -> bool {
+    if row >= LAYER_ROW {
+        return (L_PINKY..=R_PINKY).contains(&col);
+    }
+
     return match row {
         NUM_ROW => (0..=NUM_ROW_CNT).contains(&col),
         TOP_ROW => (0..=TOP_ROW_CNT).contains(&col),
         HOME_ROW => (0..=HOME_ROW_CNT).contains(&col),
         BOT_ROW => (0..=BOT_ROW_CNT).contains(&col),
+        THUMB_ROW => (L_THUMB_REACH..=R_THUMB_REACH).contains(&col),
         _ => false,
     };
 }
@@ -288,14 +441,35 @@ pub fn place_keys(
     return false;
 }
 
-pub fn get_swappable_keys(swappable_keys: &[(u8, u8); 30]) -> Vec<Key> {
+pub fn get_swappable_keys(swappable_keys: &[(char, char); 30]) -> Vec<Key> {
     return swappable_keys
         .iter()
         .copied()
-        .map(|k| return Key::from_tuple(k))
+        .map(|k| return apply_charset(Key::from_tuple(k)))
         .collect();
 }
 
+// The key charset.keys puts in place of the board key with this unshifted character
+pub fn get_charset_key(old: char) -> Option<Key> {
+    return get_config()
+        .charset
+        .keys
+        .iter()
+        .find(|c| return c.get_old() == old)
+        .map(|c| return c.get_key());
+}
+
+pub fn apply_charset(key: Key) -> Key {
+    return get_charset_key(key.get_base()).unwrap_or(key);
+}
+
+// Fixed layouts like Qwerty are written with the board's own keys
+pub fn use_charset(key_slots: &mut BTreeMap<Slot, Key>) {
+    for key in key_slots.values_mut() {
+        *key = apply_charset(*key);
+    }
+}
+
 pub fn get_static_keys(
     swappable_keys: &[Key],
     valid_locs: &[(Key, Vec<Slot>)],
@@ -312,6 +486,8 @@ pub fn get_static_keys(
         .collect();
 }
 
+// The recursion carries all of its state through arguments
+#[expect(clippy::too_many_arguments)]
 pub fn place_keys_from_table(
     rng: &mut SmallRng,
     slots: &mut Vec<Slot>,
@@ -320,6 +496,7 @@ pub fn place_keys_from_table(
     key_slots: &mut BTreeMap<Slot, Key>,
     valid_slots: &BTreeMap<Key, Vec<Slot>>,
     k_temp: f64,
+    policy: SwapPolicy,
 ) -> bool {
     if slots.is_empty() && keys.is_empty() {
         return true;
@@ -354,7 +531,13 @@ pub fn place_keys_from_table(
         return false;
     }
 
-    let select_key = select_key(rng, &mut candidates, k_temp);
+    let weights: Vec<f64> = candidates
+        .iter()
+        .map(|(_, key, _)| return slot_info[key].get_weights())
+        .collect();
+    apply_confidence(rng, &mut candidates, &weights);
+
+    let select_key = select_key(rng, &mut candidates, k_temp, policy);
     key_slots.insert(select_key.0, select_key.1);
 
     slots.remove(slot_idx);
@@ -365,7 +548,16 @@ pub fn place_keys_from_table(
         .expect("Should not have pulled a missing key");
     keys.remove(key_idx);
 
-    if place_keys_from_table(rng, slots, keys, swap_table, key_slots, valid_slots, k_temp) {
+    if place_keys_from_table(
+        rng,
+        slots,
+        keys,
+        swap_table,
+        key_slots,
+        valid_slots,
+        k_temp,
+        policy,
+    ) {
         return true;
     }
 
@@ -1,40 +1,97 @@
 extern crate alloc;
 
-use alloc::collections::BTreeMap;
+use {
+    alloc::collections::BTreeMap,
+    core::{
+        fmt,
+        str::FromStr,
+        sync::atomic::{AtomicUsize, Ordering},
+    },
+    std::sync::OnceLock,
+};
 
-use rand::{Rng as _, SeedableRng as _, rngs::SmallRng, seq::SliceRandom as _};
+use {
+    anyhow::{Error, anyhow},
+    rand::{Rng as _, SeedableRng as _, rngs::SmallRng, seq::SliceRandom as _},
+};
 
 use crate::{
-    base_eff,
-    corpus::get_corpus,
+    config::get_config,
+    corpus::{QWERTY_PCT, combine_corpus_scores, get_char_freqs, get_corpus, get_corpus_sets},
+    crossover::CrossoverOp,
     edge_cols,
-    eval_funcs::{check_key_no_hist, compare_slots, global_adjustments},
+    eval_funcs::{get_key_eff, get_layer_mult, get_load_mult},
+    home_row,
     kb_builders::{
-        get_static_keys, get_swappable_keys, get_valid_key_locs_sorted, place_dvorak_keys,
-        place_keys, place_keys_from_table, place_qwerty_keys,
+        check_col, fill_layer_blanks, get_iso_swap_slot, get_layer_slots, get_static_keys,
+        get_swappable_keys, get_valid_key_locs_sorted, move_to_iso, move_to_layers,
+        place_dvorak_keys, place_keys, place_keys_from_table, place_qwerty_keys, use_charset,
     },
-    keys,
-    mapped_swap::{get_improvement, select_key, shuffle_check},
+    keys, layer_rows,
+    layout_file::{escape_key, unescape_key},
+    mapped_swap::{SwapPolicy, apply_confidence, get_improvement, select_key, shuffle_check},
     most_cols, most_rows,
     population::SwapTable,
     structs::{Key, Slot},
-    swappable_keys,
+    swappable_keys, thumb_cluster, thumb_row,
 };
 
 pub const ASCII_CNT: usize = 128;
 
+// Counts full corpus evaluations across every keyboard so run-level limits can be enforced
+static EVAL_CNT: AtomicUsize = AtomicUsize::new(0);
+
+pub fn get_eval_cnt() -> usize {
+    return EVAL_CNT.load(Ordering::Relaxed);
+}
+
+// Qwerty's score on each of corpus.sets, built on first use. Not counted as evaluations
+static QWERTY_BASELINES: OnceLock<Vec<f64>> = OnceLock::new();
+
+// initialize_corpus checks that each baseline is above 0
+pub fn get_qwerty_baselines() -> &'static Vec<f64> {
+    return QWERTY_BASELINES.get_or_init(|| {
+        let mut qwerty = Keyboard::create_qwerty();
+        return get_corpus_sets()
+            .iter()
+            .map(|set| return qwerty.eval_entries(set.get_entries()))
+            .collect();
+    });
+}
+
 most_cols!();
 edge_cols!();
+home_row!();
 most_rows!();
-base_eff!();
+layer_rows!();
 swappable_keys!();
+thumb_cluster!();
+
+// Built from thumb.keys on first use. Thumb keys stay put for the whole run
+static THUMB_KEYS: OnceLock<BTreeMap<Slot, Key>> = OnceLock::new();
+
+pub fn get_thumb_keys() -> &'static BTreeMap<Slot, Key> {
+    return THUMB_KEYS.get_or_init(|| {
+        return get_config()
+            .thumb
+            .keys
+            .iter()
+            .map(|t| {
+                return (
+                    t.get_pos().get_slot(),
+                    Key::from_tuple((t.get_key(), t.get_key())),
+                );
+            })
+            .collect();
+    });
+}
 
 pub enum KeyCompare {
     Mult(f64),
     Mismatch,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Hand {
     Left,
     Right,
@@ -42,43 +99,801 @@ pub enum Hand {
 
 impl Hand {
     /// # Panics
-    /// Panics if the input col is invalid
+    /// Panics if the input col is invalid.
     pub fn from_slot(slot: Slot) -> Self {
-        return match slot.get_col() {
-            L_PINKY..=L_EXT => Hand::Left,
-            R_EXT..=R_PIPE => Hand::Right,
-            _ => panic!("Col {} is invalid in get_hand", slot.get_col()),
-        };
+        return get_finger_id(slot).get_hand();
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum Finger {
     Pinky,
     Ring,
     Middle,
     Index,
+    Thumb,
 }
 
 impl Finger {
     pub fn from_slot(slot: Slot) -> Self {
-        return match slot.get_col() {
-            L_PINKY | R_PINKY..=R_PIPE => Finger::Pinky,
-            L_RING | R_RING => Finger::Ring,
-            L_MIDDLE | R_MIDDLE => Finger::Middle,
-            L_INDEX..=R_INDEX => Finger::Index,
-            _ => panic!("Col {} is invalid in get_hand", slot.get_col()),
+        return get_finger_id(slot).get_finger();
+    }
+
+    // Where the finger typing the slot rests. Keys outside the home columns, such as the index
+    // extensions and the right pinky symbols, are reached from that finger's home key
+    pub fn get_home_slot(slot: Slot) -> Slot {
+        return get_finger_homes()[get_finger_id(slot).get_index()];
+    }
+}
+
+// Fingers from the left pinky to the right pinky, counting both thumbs
+pub const FINGER_CNT: usize = 10;
+
+// A finger on a given hand. Which one types each slot comes from fingers.columns and
+// fingers.overrides
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub struct FingerId {
+    hand: Hand,
+    finger: Finger,
+}
+
+impl FingerId {
+    pub fn new(hand: Hand, finger: Finger) -> Self {
+        return Self { hand, finger };
+    }
+
+    pub fn get_hand(self) -> Hand {
+        return self.hand;
+    }
+
+    pub fn get_finger(self) -> Finger {
+        return self.finger;
+    }
+
+    // Position from the left pinky (0) to the right pinky (FINGER_CNT - 1)
+    pub fn get_index(self) -> usize {
+        let from_pinky = match self.finger {
+            Finger::Pinky => 0,
+            Finger::Ring => 1,
+            Finger::Middle => 2,
+            Finger::Index => 3,
+            Finger::Thumb => 4,
+        };
+
+        return match self.hand {
+            Hand::Left => from_pinky,
+            Hand::Right => FINGER_CNT - 1 - from_pinky,
+        };
+    }
+
+    // The standard touch typing assignment. The index fingers take the two center columns and
+    // the right pinky everything past its home column
+    pub fn from_col(col: usize) -> Self {
+        let (hand, finger) = match col {
+            L_PINKY => (Hand::Left, Finger::Pinky),
+            L_RING => (Hand::Left, Finger::Ring),
+            L_MIDDLE => (Hand::Left, Finger::Middle),
+            L_INDEX..=L_EXT => (Hand::Left, Finger::Index),
+            R_EXT..=R_INDEX => (Hand::Right, Finger::Index),
+            R_MIDDLE => (Hand::Right, Finger::Middle),
+            R_RING => (Hand::Right, Finger::Ring),
+            R_PINKY..=R_PIPE => (Hand::Right, Finger::Pinky),
+            _ => panic!("Col {col} is invalid in FingerId::from_col"),
+        };
+
+        return Self { hand, finger };
+    }
+
+    // The home row column the finger rests on under the standard assignment
+    fn get_default_home_col(self) -> usize {
+        return match (self.hand, self.finger) {
+            (Hand::Left, Finger::Pinky) => L_PINKY,
+            (Hand::Left, Finger::Ring) => L_RING,
+            (Hand::Left, Finger::Middle) => L_MIDDLE,
+            (Hand::Left, Finger::Index) => L_INDEX,
+            (Hand::Right, Finger::Index) => R_INDEX,
+            (Hand::Right, Finger::Middle) => R_MIDDLE,
+            (Hand::Right, Finger::Ring) => R_RING,
+            (Hand::Right, Finger::Pinky) => R_PINKY,
+            (Hand::Left, Finger::Thumb) => L_THUMB,
+            (Hand::Right, Finger::Thumb) => R_THUMB,
+        };
+    }
+}
+
+impl fmt::Display for FingerId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let hand = match self.hand {
+            Hand::Left => 'l',
+            Hand::Right => 'r',
+        };
+
+        let finger = match self.finger {
+            Finger::Pinky => 'p',
+            Finger::Ring => 'r',
+            Finger::Middle => 'm',
+            Finger::Index => 'i',
+            Finger::Thumb => 't',
+        };
+
+        return write!(f, "{hand}{finger}");
+    }
+}
+
+impl FromStr for FingerId {
+    type Err = Error;
+
+    // Two letters, hand then finger, such as lp for the left pinky or ri for the right index
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        let mut chars = lower.chars();
+        let (Some(hand_char), Some(finger_char), None) =
+            (chars.next(), chars.next(), chars.next())
+        else {
+            return Err(anyhow!("expected a hand and a finger, such as lp or ri"));
+        };
+
+        let hand = match hand_char {
+            'l' => Hand::Left,
+            'r' => Hand::Right,
+            _ => return Err(anyhow!("hand must be l or r")),
+        };
+
+        let finger = match finger_char {
+            'p' => Finger::Pinky,
+            'r' => Finger::Ring,
+            'm' => Finger::Middle,
+            'i' => Finger::Index,
+            't' => Finger::Thumb,
+            _ => return Err(anyhow!("finger must be p, r, m, i, or t")),
+        };
+
+        return Ok(Self { hand, finger });
+    }
+}
+
+// Reassigns one slot of the main rows to another finger, written row:col:finger, such as
+// bot:1:lm. Rows are num, top, home, or bot
+#[derive(Debug, Clone, Copy)]
+pub struct FingerOverride {
+    slot: Slot,
+    finger: FingerId,
+}
+
+impl FingerOverride {
+    pub fn get_slot(self) -> Slot {
+        return self.slot;
+    }
+
+    pub fn get_finger(self) -> FingerId {
+        return self.finger;
+    }
+}
+
+impl FromStr for FingerOverride {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split(':').map(str::trim);
+        let (Some(row_str), Some(col_str), Some(finger_str), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return Err(anyhow!("expected row:col:finger, such as bot:1:lm"));
+        };
+
+        let row = match row_str.to_lowercase().as_str() {
+            "num" => NUM_ROW,
+            "top" => TOP_ROW,
+            "home" => HOME_ROW,
+            "bot" => BOT_ROW,
+            _ => return Err(anyhow!("row must be num, top, home, or bot")),
         };
+
+        let col: usize = col_str.parse()?;
+        if !check_col(row, col) {
+            return Err(anyhow!("col {col} is not on row {row_str}"));
+        }
+
+        return Ok(Self {
+            slot: Slot::from_tuple((row, col)),
+            finger: finger_str.parse()?,
+        });
     }
 }
 
+// Named changes to how the hands sit on a standard board. Each reassigns some slots to other
+// fingers, applied before fingers.overrides, and may move some fingers' home keys
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErgoMod {
+    // The left wrist angles in, so the bottom row is typed one finger over: the ring finger takes
+    // Z, the middle X, and the index C
+    Angle,
+    // The right hand rests one column further out, on K L ; '. Each right finger takes the column
+    // to the right of its usual one, and the index covers H, J, and K
+    Wide,
+}
+
+impl ErgoMod {
+    fn get_overrides(self) -> Vec<(Slot, FingerId)> {
+        let left = |finger: Finger| return FingerId::new(Hand::Left, finger);
+        let right = |finger: Finger| return FingerId::new(Hand::Right, finger);
+
+        return match self {
+            ErgoMod::Angle => vec![
+                (Slot::from_tuple((BOT_ROW, L_PINKY)), left(Finger::Ring)),
+                (Slot::from_tuple((BOT_ROW, L_RING)), left(Finger::Middle)),
+                (Slot::from_tuple((BOT_ROW, L_MIDDLE)), left(Finger::Index)),
+            ],
+            ErgoMod::Wide => {
+                let mut overrides: Vec<(Slot, FingerId)> = Vec::new();
+                for row in NUM_ROW..=BOT_ROW {
+                    for col in (R_EXT..=R_PIPE).filter(|c| return check_col(row, *c)) {
+                        let finger = match col {
+                            R_EXT..=R_MIDDLE => Finger::Index,
+                            R_RING => Finger::Middle,
+                            R_PINKY => Finger::Ring,
+                            _ => Finger::Pinky,
+                        };
+
+                        overrides.push((Slot::from_tuple((row, col)), right(finger)));
+                    }
+                }
+
+                overrides
+            }
+        };
+    }
+
+    // Home row column of each finger the mod moves
+    fn get_homes(self) -> Vec<(FingerId, usize)> {
+        let right = |finger: Finger| return FingerId::new(Hand::Right, finger);
+
+        return match self {
+            ErgoMod::Angle => Vec::new(),
+            ErgoMod::Wide => vec![
+                (right(Finger::Index), R_MIDDLE),
+                (right(Finger::Middle), R_RING),
+                (right(Finger::Ring), R_PINKY),
+                (right(Finger::Pinky), R_PINKY + 1),
+            ],
+        };
+    }
+}
+
+impl fmt::Display for ErgoMod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            ErgoMod::Angle => write!(f, "angle"),
+            ErgoMod::Wide => write!(f, "wide"),
+        };
+    }
+}
+
+impl FromStr for ErgoMod {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "angle" => Ok(ErgoMod::Angle),
+            "wide" => Ok(ErgoMod::Wide),
+            _ => Err(anyhow!("expected angle or wide")),
+        };
+    }
+}
+
+pub fn has_mod(ergo_mod: ErgoMod) -> bool {
+    return get_config().fingers.mods.contains(&ergo_mod);
+}
+
+// Built from fingers.columns, fingers.mods, and fingers.overrides on first use. Indexed by row,
+// then col, for the main rows
+static FINGER_MAP: OnceLock<Vec<Vec<FingerId>>> = OnceLock::new();
+// Home slot of each finger, by FingerId::get_index
+static FINGER_HOMES: OnceLock<Vec<Slot>> = OnceLock::new();
+// Same layout as FINGER_MAP
+static RULE_COLS: OnceLock<Vec<Vec<usize>>> = OnceLock::new();
+
+fn get_finger_map() -> &'static Vec<Vec<FingerId>> {
+    return FINGER_MAP.get_or_init(|| {
+        let config = &get_config().fingers;
+
+        let mut finger_map = vec![config.columns.clone(); BOT_ROW + 1];
+        let mod_overrides = config.mods.iter().flat_map(|m| return m.get_overrides());
+        let user_overrides = config
+            .overrides
+            .iter()
+            .map(|o| return (o.get_slot(), o.get_finger()));
+        // The ISO key is the left pinky's, however the rest of its column is assigned. The rules
+        // score it as that finger's bottom row key, and only distance scoring sees the extra reach
+        let iso_override = get_iso_slot()
+            .into_iter()
+            .map(|s| return (s, FingerId::new(Hand::Left, Finger::Pinky)));
+        for (slot, finger_id) in mod_overrides.chain(iso_override).chain(user_overrides) {
+            finger_map[slot.get_row()][slot.get_col()] = finger_id;
+        }
+
+        return finger_map;
+    });
+}
+
+// A finger rests on the home row column it was given that is nearest its usual home, or the home
+// its mod names. A finger with no home row column keeps that home
+fn get_finger_homes() -> &'static Vec<Slot> {
+    return FINGER_HOMES.get_or_init(|| {
+        let home_row = &get_finger_map()[HOME_ROW];
+        let mod_homes: Vec<(FingerId, usize)> = get_config()
+            .fingers
+            .mods
+            .iter()
+            .flat_map(|m| return m.get_homes())
+            .collect();
+        let fingers = [
+            Finger::Pinky,
+            Finger::Ring,
+            Finger::Middle,
+            Finger::Index,
+            Finger::Thumb,
+        ];
+
+        let mut homes = vec![Slot::from_tuple((HOME_ROW, 0)); FINGER_CNT];
+        for hand in [Hand::Left, Hand::Right] {
+            for finger in fingers {
+                let finger_id = FingerId { hand, finger };
+                let default_col = mod_homes
+                    .iter()
+                    .rev()
+                    .find(|h| return h.0 == finger_id)
+                    .map_or(finger_id.get_default_home_col(), |h| return h.1);
+                let home = if finger == Finger::Thumb {
+                    Slot::from_tuple((THUMB_ROW, default_col))
+                } else {
+                    // The home row has no pipe column
+                    let home_col = (0..R_PIPE)
+                        .filter(|col| return home_row[*col] == finger_id)
+                        .min_by_key(|col| return col.abs_diff(default_col))
+                        .unwrap_or(default_col);
+                    Slot::from_tuple((HOME_ROW, home_col))
+                };
+
+                homes[finger_id.get_index()] = home;
+            }
+        }
+
+        return homes;
+    });
+}
+
+// The efficiency rules name keys by their column on a standard board, such as the index
+// extension or the pinky symbol columns. This is the standard column whose reach matches the
+// slot's: the same distance from its finger's usual home as the slot is from the finger's home,
+// kept within the columns that finger usually covers. Without reassignments it is the slot's
+// own column
+pub fn get_rule_col(slot: Slot) -> usize {
+    let phys = slot.get_phys();
+    if phys.get_row() == THUMB_ROW {
+        return phys.get_col();
+    }
+
+    return get_rule_cols()[phys.get_row()][phys.get_col()];
+}
+
+fn get_rule_cols() -> &'static Vec<Vec<usize>> {
+    return RULE_COLS.get_or_init(|| {
+        let homes = get_finger_homes();
+
+        return get_finger_map()
+            .iter()
+            .map(|row| {
+                return row
+                    .iter()
+                    .enumerate()
+                    .map(|(col, finger_id)| {
+                        let std_cols: Vec<usize> = (L_PINKY..=R_PIPE)
+                            .filter(|c| return FingerId::from_col(*c) == *finger_id)
+                            .collect();
+                        let home_col = homes[finger_id.get_index()].get_col();
+                        let rule_col =
+                            (finger_id.get_default_home_col() + col).saturating_sub(home_col);
+
+                        return rule_col.clamp(std_cols[0], std_cols[std_cols.len() - 1]);
+                    })
+                    .collect();
+            })
+            .collect();
+    });
+}
+
+const THUMB_IDS: [FingerId; 2] = [
+    FingerId {
+        hand: Hand::Left,
+        finger: Finger::Thumb,
+    },
+    FingerId {
+        hand: Hand::Right,
+        finger: Finger::Thumb,
+    },
+];
+
+// Thumb keys always belong to the thumb on their side. Layer slots use their physical key
+pub fn get_finger_id(slot: Slot) -> FingerId {
+    let phys = slot.get_phys();
+    if phys.get_row() == THUMB_ROW {
+        if phys.get_col() <= L_EXT {
+            return THUMB_IDS[0];
+        }
+
+        return THUMB_IDS[1];
+    }
+
+    return get_finger_map()[phys.get_row()][phys.get_col()];
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ThumbPos {
+    Left,
+    LeftReach,
+    Right,
+    RightReach,
+}
+
+impl ThumbPos {
+    pub fn get_slot(self) -> Slot {
+        return match self {
+            ThumbPos::Left => Slot::from_tuple((THUMB_ROW, L_THUMB)),
+            ThumbPos::LeftReach => Slot::from_tuple((THUMB_ROW, L_THUMB_REACH)),
+            ThumbPos::Right => Slot::from_tuple((THUMB_ROW, R_THUMB)),
+            ThumbPos::RightReach => Slot::from_tuple((THUMB_ROW, R_THUMB_REACH)),
+        };
+    }
+}
+
+impl fmt::Display for ThumbPos {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            ThumbPos::Left => write!(f, "left"),
+            ThumbPos::LeftReach => write!(f, "left_reach"),
+            ThumbPos::Right => write!(f, "right"),
+            ThumbPos::RightReach => write!(f, "right_reach"),
+        };
+    }
+}
+
+impl FromStr for ThumbPos {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "left" => Ok(ThumbPos::Left),
+            "left_reach" => Ok(ThumbPos::LeftReach),
+            "right" => Ok(ThumbPos::Right),
+            "right_reach" => Ok(ThumbPos::RightReach),
+            _ => Err(anyhow!("expected left, left_reach, right, or right_reach")),
+        };
+    }
+}
+
+// A character typed with a thumb, written as key:position. Space is written as "space", and
+// newline and backslash are escaped the same way as in layout files
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ThumbKey {
+    key: char,
+    pos: ThumbPos,
+}
+
+impl ThumbKey {
+    pub fn get_key(self) -> char {
+        return self.key;
+    }
+
+    pub fn get_pos(self) -> ThumbPos {
+        return self.pos;
+    }
+}
+
+impl FromStr for ThumbKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((key_str, pos_str)) = s.split_once(':') else {
+            return Err(anyhow!("expected key:position, such as space:right"));
+        };
+
+        let key = if key_str.eq_ignore_ascii_case("space") {
+            ' '
+        } else {
+            unescape_key(key_str)?
+        };
+
+        return Ok(Self {
+            key,
+            pos: pos_str.parse()?,
+        });
+    }
+}
+
+// An ISO board adds a key between left shift and Z, and its tall Enter pushes the key above it
+// down beside Enter on the home row
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Board {
+    Ansi,
+    Iso,
+}
+
+impl fmt::Display for Board {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Board::Ansi => write!(f, "ansi"),
+            Board::Iso => write!(f, "iso"),
+        };
+    }
+}
+
+impl FromStr for Board {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "ansi" => Ok(Board::Ansi),
+            "iso" => Ok(Board::Iso),
+            _ => Err(anyhow!("expected ansi or iso")),
+        };
+    }
+}
+
+// What the extra ISO key holds. A swappable ISO key starts out blank, and any key allowed in the
+// bottom left pinky slot can move there. A static key is written as key or key:shifted key, with
+// the same escapes as layout files
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum IsoKey {
+    Swappable,
+    Empty,
+    Static(Key),
+}
+
+impl fmt::Display for IsoKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            IsoKey::Swappable => write!(f, "swappable"),
+            IsoKey::Empty => write!(f, "none"),
+            IsoKey::Static(key) if key.get_base() == key.get_shift() => {
+                write!(f, "{}", escape_key(key.get_base()))
+            }
+            IsoKey::Static(key) => write!(
+                f,
+                "{}:{}",
+                escape_key(key.get_base()),
+                escape_key(key.get_shift())
+            ),
+        };
+    }
+}
+
+impl FromStr for IsoKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "swappable" => return Ok(IsoKey::Swappable),
+            "none" => return Ok(IsoKey::Empty),
+            _ => {}
+        }
+
+        let (base_str, shift_str) = s.split_once(':').unwrap_or((s, s));
+        let base = unescape_key(base_str)?;
+        let shift = unescape_key(shift_str)?;
+        if base == '\0' || shift == '\0' {
+            return Err(anyhow!("expected swappable, none, or a key"));
+        }
+
+        return Ok(IsoKey::Static(Key::from_tuple((base, shift))));
+    }
+}
+
+// A key of another language's character set, put in place of a board key. Written as old:key or
+// old:key:shifted key, such as ;:ö:Ö. The shifted key defaults to the key's capital. The new key
+// takes over the old key's slots, and is swappable if the old key was
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CharsetKey {
+    old: char,
+    key: Key,
+}
+
+impl CharsetKey {
+    pub fn get_old(self) -> char {
+        return self.old;
+    }
+
+    pub fn get_key(self) -> Key {
+        return self.key;
+    }
+}
+
+impl fmt::Display for CharsetKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(
+            f,
+            "{}:{}:{}",
+            escape_key(self.old),
+            escape_key(self.key.get_base()),
+            escape_key(self.key.get_shift())
+        );
+    }
+}
+
+impl FromStr for CharsetKey {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((old_str, key_str)) = s.split_once(':') else {
+            return Err(anyhow!("expected old:key or old:key:shifted key"));
+        };
+
+        let old = unescape_key(old_str)?;
+        let (base, shift) = if let Some((base_str, shift_str)) = key_str.split_once(':') {
+            (unescape_key(base_str)?, unescape_key(shift_str)?)
+        } else {
+            let base = unescape_key(key_str)?;
+            let mut upper = base.to_uppercase();
+            let shift = match (upper.next(), upper.next()) {
+                (Some(c), None) => c,
+                _ => base,
+            };
+
+            (base, shift)
+        };
+
+        if [old, base, shift].contains(&'\0') {
+            return Err(anyhow!("expected old:key or old:key:shifted key"));
+        }
+
+        return Ok(Self {
+            old,
+            key: Key::from_tuple((base, shift)),
+        });
+    }
+}
+
+// A character typed as a dead key followed by a base key, such as é from ´ then e.
+// Written as composed:dead:base. A dead key types nothing by itself, so its own character takes
+// the dead key then space
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DeadKeySeq {
+    composed: char,
+    dead: char,
+    base: char,
+}
+
+impl DeadKeySeq {
+    pub fn get_composed(self) -> char {
+        return self.composed;
+    }
+
+    pub fn get_dead(self) -> char {
+        return self.dead;
+    }
+
+    pub fn get_base(self) -> char {
+        return self.base;
+    }
+}
+
+impl fmt::Display for DeadKeySeq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let base = if self.base == ' ' {
+            "space".to_string()
+        } else {
+            escape_key(self.base)
+        };
+
+        return write!(
+            f,
+            "{}:{}:{base}",
+            escape_key(self.composed),
+            escape_key(self.dead)
+        );
+    }
+}
+
+impl FromStr for DeadKeySeq {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let tokens: Vec<&str> = s.split(':').collect();
+        let [composed_str, dead_str, base_str] = tokens[..] else {
+            return Err(anyhow!("expected composed:dead:base"));
+        };
+
+        let composed = unescape_key(composed_str)?;
+        let dead = unescape_key(dead_str)?;
+        let base = if base_str.eq_ignore_ascii_case("space") {
+            ' '
+        } else {
+            unescape_key(base_str)?
+        };
+
+        if [composed, dead, base].contains(&'\0') || composed == dead || composed == base {
+            return Err(anyhow!("expected composed:dead:base"));
+        }
+
+        return Ok(Self {
+            composed,
+            dead,
+            base,
+        });
+    }
+}
+
+// The XKB keysym of each dead key. Both the spacing accents and their usual ASCII stand-ins name
+// one. dead_keys.sequences is checked against this when the config is loaded, so the XKB export
+// can always write its dead keys
+pub fn xkb_dead_keysym(c: char) -> Result<String, Error> {
+    let name = match c {
+        '`' => "dead_grave",
+        '\'' | '\u{b4}' => "dead_acute",
+        '^' => "dead_circumflex",
+        '~' => "dead_tilde",
+        '"' | '\u{a8}' => "dead_diaeresis",
+        '\u{b8}' => "dead_cedilla",
+        '\u{b0}' => "dead_abovering",
+        '\u{af}' => "dead_macron",
+        '\u{2c7}' => "dead_caron",
+        '\u{2d8}' => "dead_breve",
+        '\u{2d9}' => "dead_abovedot",
+        '\u{2db}' => "dead_ogonek",
+        '\u{2dd}' => "dead_doubleacute",
+        _ => return Err(anyhow!("No XKB dead keysym for \"{}\"", c)),
+    };
+
+    return Ok(name.to_string());
+}
+
+// The ISO key is stored after the rest of the bottom row, in a column the ANSI bottom row does not
+// use
+pub const ISO_COL: usize = 10;
+
+// Only set on an ISO board
+pub fn get_iso_slot() -> Option<Slot> {
+    if get_config().geometry.board == Board::Iso {
+        return Some(Slot::from_tuple((BOT_ROW, ISO_COL)));
+    }
+
+    return None;
+}
+
+pub fn is_iso_slot(slot: Slot) -> bool {
+    return get_iso_slot() == Some(slot);
+}
+
+// How many columns apart two slots on the same row sit. The ISO key is one column left of the
+// bottom row's first key, even though it is stored at the end of the row
+pub fn get_col_dist(this_slot: Slot, last_slot: Slot) -> usize {
+    let pos = |slot: Slot| {
+        if is_iso_slot(slot.get_phys()) {
+            return 0;
+        }
+
+        return slot.get_col() + 1;
+    };
+
+    return pos(this_slot).abs_diff(pos(last_slot));
+}
+
+// Where a slot sits along its row in key widths, before any stagger
+pub fn get_col_pos(slot: Slot) -> f64 {
+    if is_iso_slot(slot.get_phys()) {
+        return -1.0_f64;
+    }
+
+    return slot.get_col() as f64;
+}
+
 // FUTURE: Valid_slots is a meta-population level construct
 // NOTE: Do not derive Clone, because that does not advance the keyboard's RNG state
 pub struct Keyboard {
     rng: SmallRng,
     key_slots: BTreeMap<Slot, Key>,
     valid_slots: BTreeMap<Key, Vec<Slot>>,
-    slot_ascii: Vec<Option<Slot>>,
+    slot_chars: CharSlots,
     last_slot_idx: Option<Slot>,
     prev_slot_idx: Option<Slot>,
     generation: usize,
@@ -87,17 +902,19 @@ pub struct Keyboard {
     score: f64,
     left_uses: f64,
     right_uses: f64,
+    finger_uses: [f64; FINGER_CNT],
     is_elite: bool,
     pos_iter: usize,
     last_score: f64,
     last_swap_a: (Slot, Key),
     last_swap_b: (Slot, Key),
+    crossover: Option<CrossoverOp>,
 }
 
 impl Keyboard {
     /// # Panics
     /// The specs to build the keyboard properly are defined at compile time. If the specs are
-    /// incorrect, this function or one of its sub-functions will panic
+    /// incorrect, this function or one of its sub-functions will panic.
     pub fn create_primo(id_in: usize) -> Self {
         let seed: [u8; 32] = rand::random();
         let rng = SmallRng::from_seed(seed);
@@ -108,20 +925,17 @@ impl Keyboard {
             place_keys(&mut key_slots, &valid_key_locs_sorted, 0),
             "Unable to place all keys"
         );
+        fill_layer_blanks(&mut key_slots);
 
         let valid_slots: BTreeMap<Key, Vec<Slot>> = valid_key_locs_sorted.into_iter().collect();
 
-        let mut slot_ascii: Vec<Option<Slot>> = vec![None; ASCII_CNT];
-        for (slot, key) in &key_slots {
-            slot_ascii[usize::from(key.get_base())] = Some(*slot);
-            slot_ascii[usize::from(key.get_shift())] = Some(*slot);
-        }
+        let slot_chars = get_char_slots(&key_slots);
 
         return Self {
             rng,
             key_slots,
             valid_slots,
-            slot_ascii,
+            slot_chars,
             last_slot_idx: None,
             prev_slot_idx: None,
             generation: 0,
@@ -130,11 +944,13 @@ impl Keyboard {
             score: 0.0,
             left_uses: 0.0,
             right_uses: 0.0,
+            finger_uses: [0.0; FINGER_CNT],
             is_elite: false,
             pos_iter: 0,
             last_score: 0.0,
-            last_swap_a: (Slot::from_tuple((0, 0)), Key::from_tuple((0, 0))),
-            last_swap_b: (Slot::from_tuple((0, 0)), Key::from_tuple((0, 0))),
+            last_swap_a: (Slot::from_tuple((0, 0)), Key::BLANK),
+            last_swap_b: (Slot::from_tuple((0, 0)), Key::BLANK),
+            crossover: None,
         };
     }
 
@@ -146,7 +962,7 @@ impl Keyboard {
             rng,
             key_slots: self.key_slots.clone(),
             valid_slots: self.valid_slots.clone(),
-            slot_ascii: self.slot_ascii.clone(),
+            slot_chars: self.slot_chars.clone(),
             last_slot_idx: None,
             prev_slot_idx: None,
             generation: self.generation,
@@ -155,68 +971,49 @@ impl Keyboard {
             score: self.score,
             left_uses: 0.0,
             right_uses: 0.0,
+            finger_uses: [0.0; FINGER_CNT],
             is_elite: self.is_elite,
             pos_iter: self.pos_iter,
             last_score: self.last_score,
-            last_swap_a: (Slot::from_tuple((0, 0)), Key::from_tuple((0, 0))),
-            last_swap_b: (Slot::from_tuple((0, 0)), Key::from_tuple((0, 0))),
+            last_swap_a: (Slot::from_tuple((0, 0)), Key::BLANK),
+            last_swap_b: (Slot::from_tuple((0, 0)), Key::BLANK),
+            crossover: self.crossover,
         };
     }
 
     pub fn create_qwerty() -> Self {
-        let seed: [u8; 32] = rand::random();
-        let rng = SmallRng::from_seed(seed);
         let mut key_slots: BTreeMap<Slot, Key> = BTreeMap::new();
-        let valid_key_locs_sorted: Vec<(Key, Vec<Slot>)> = get_valid_key_locs_sorted();
         place_qwerty_keys(&mut key_slots);
-        let valid_slots: BTreeMap<Key, Vec<Slot>> = valid_key_locs_sorted.into_iter().collect();
 
-        let mut slot_ascii: Vec<Option<Slot>> = vec![None; ASCII_CNT];
-        for (slot, key) in &key_slots {
-            slot_ascii[usize::from(key.get_base())] = Some(*slot);
-            slot_ascii[usize::from(key.get_shift())] = Some(*slot);
-        }
-
-        return Self {
-            rng,
-            key_slots,
-            valid_slots,
-            slot_ascii,
-            last_slot_idx: None,
-            prev_slot_idx: None,
-            generation: 0,
-            id: 0,
-            evaluated: false,
-            score: 0.0,
-            left_uses: 0.0,
-            right_uses: 0.0,
-            is_elite: false,
-            pos_iter: 0,
-            last_score: 0.0,
-            last_swap_a: (Slot::from_tuple((0, 0)), Key::from_tuple((0, 0))),
-            last_swap_b: (Slot::from_tuple((0, 0)), Key::from_tuple((0, 0))),
-        };
+        return Self::from_key_slots(key_slots);
     }
 
     pub fn create_dvorak() -> Self {
+        let mut key_slots: BTreeMap<Slot, Key> = BTreeMap::new();
+        place_dvorak_keys(&mut key_slots);
+
+        return Self::from_key_slots(key_slots);
+    }
+
+    // The caller is responsible for providing a complete layout. Keys are not checked against
+    // their valid locations, so fixed layouts like Qwerty can be represented
+    pub fn from_key_slots(mut key_slots: BTreeMap<Slot, Key>) -> Self {
+        use_charset(&mut key_slots);
+        move_to_layers(&mut key_slots);
+        move_to_iso(&mut key_slots);
+
         let seed: [u8; 32] = rand::random();
         let rng = SmallRng::from_seed(seed);
-        let mut key_slots: BTreeMap<Slot, Key> = BTreeMap::new();
         let valid_key_locs_sorted: Vec<(Key, Vec<Slot>)> = get_valid_key_locs_sorted();
-        place_dvorak_keys(&mut key_slots);
         let valid_slots: BTreeMap<Key, Vec<Slot>> = valid_key_locs_sorted.into_iter().collect();
 
-        let mut slot_ascii: Vec<Option<Slot>> = vec![None; ASCII_CNT];
-        for (slot, key) in &key_slots {
-            slot_ascii[usize::from(key.get_base())] = Some(*slot);
-            slot_ascii[usize::from(key.get_shift())] = Some(*slot);
-        }
+        let slot_chars = get_char_slots(&key_slots);
 
         return Self {
             rng,
             key_slots,
             valid_slots,
-            slot_ascii,
+            slot_chars,
             last_slot_idx: None,
             prev_slot_idx: None,
             generation: 0,
@@ -225,11 +1022,35 @@ impl Keyboard {
             score: 0.0,
             left_uses: 0.0,
             right_uses: 0.0,
+            finger_uses: [0.0; FINGER_CNT],
             is_elite: false,
             pos_iter: 0,
             last_score: 0.0,
-            last_swap_a: (Slot::from_tuple((0, 0)), Key::from_tuple((0, 0))),
-            last_swap_b: (Slot::from_tuple((0, 0)), Key::from_tuple((0, 0))),
+            last_swap_a: (Slot::from_tuple((0, 0)), Key::BLANK),
+            last_swap_b: (Slot::from_tuple((0, 0)), Key::BLANK),
+            crossover: None,
+        };
+    }
+
+    pub fn from_crossover(
+        key_slots: BTreeMap<Slot, Key>,
+        gen_in: usize,
+        id_in: usize,
+        op: CrossoverOp,
+    ) -> Self {
+        let mut kb = Self::from_key_slots(key_slots);
+        kb.generation = gen_in;
+        kb.id = id_in;
+        kb.crossover = Some(op);
+
+        return kb;
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        return match name.to_lowercase().as_str() {
+            "qwerty" => Some(Self::create_qwerty()),
+            "dvorak" => Some(Self::create_dvorak()),
+            _ => None,
         };
     }
 
@@ -238,6 +1059,7 @@ impl Keyboard {
         gen_in: usize,
         id_in: usize,
         k_temp: f64,
+        policy: SwapPolicy,
     ) -> Self {
         let seed: [u8; 32] = rand::random();
         let mut rng = SmallRng::from_seed(seed);
@@ -263,22 +1085,20 @@ impl Keyboard {
                 &mut key_slots,
                 &valid_slots,
                 k_temp,
+                policy,
             ) {
                 break;
             }
         }
+        fill_layer_blanks(&mut key_slots);
 
-        let mut slot_ascii: Vec<Option<Slot>> = vec![None; ASCII_CNT];
-        for (slot, key) in &key_slots {
-            slot_ascii[usize::from(key.get_base())] = Some(*slot);
-            slot_ascii[usize::from(key.get_shift())] = Some(*slot);
-        }
+        let slot_chars = get_char_slots(&key_slots);
 
         return Self {
             rng,
             key_slots,
             valid_slots,
-            slot_ascii,
+            slot_chars,
             last_slot_idx: None,
             prev_slot_idx: None,
             generation: gen_in,
@@ -287,11 +1107,13 @@ impl Keyboard {
             score: 0.0,
             left_uses: 0.0,
             right_uses: 0.0,
+            finger_uses: [0.0; FINGER_CNT],
             is_elite: false,
             pos_iter: 0,
             last_score: 0.0,
-            last_swap_a: (Slot::from_tuple((0, 0)), Key::from_tuple((0, 0))),
-            last_swap_b: (Slot::from_tuple((0, 0)), Key::from_tuple((0, 0))),
+            last_swap_a: (Slot::from_tuple((0, 0)), Key::BLANK),
+            last_swap_b: (Slot::from_tuple((0, 0)), Key::BLANK),
+            crossover: None,
         };
     }
 
@@ -314,10 +1136,19 @@ impl Keyboard {
         self.evaluated = false;
         self.score = 0.0_f64;
 
+        // Layer slots and a swappable ISO key are picked in proportion to how many there are
+        let mut extra_slots = get_layer_slots();
+        extra_slots.extend(get_iso_swap_slot());
+        let alpha_cnt = (BOT_ROW - TOP_ROW + 1) * (R_PINKY - L_PINKY + 1);
         for _ in 0..cnt {
-            let row_a = self.rng.random_range(TOP_ROW..=BOT_ROW);
-            let col_a = self.rng.random_range(L_PINKY..=R_PINKY);
-            let slot_a = Slot::from_tuple((row_a, col_a));
+            let pick = self.rng.random_range(0..alpha_cnt + extra_slots.len());
+            let slot_a = if pick < alpha_cnt {
+                let row_a = TOP_ROW + pick / (R_PINKY + 1);
+                let col_a = L_PINKY + pick % (R_PINKY + 1);
+                Slot::from_tuple((row_a, col_a))
+            } else {
+                extra_slots[pick - alpha_cnt]
+            };
             let key_a = self.key_slots[&slot_a];
 
             if let Some(vec) = self.valid_slots.get_mut(&key_a) {
@@ -353,7 +1184,7 @@ impl Keyboard {
 
     // FUTURE: Right now the kb swap functions and the swap map build explicitly exclude anything
     // outside the alpha area. This works until we want to start locking individual keys
-    pub fn table_swap(&mut self, swap_table: &SwapTable, k_temp: f64) -> bool {
+    pub fn table_swap(&mut self, swap_table: &SwapTable, k_temp: f64, policy: SwapPolicy) -> bool {
         self.evaluated = false;
         self.last_score = self.score;
         self.score = 0.0_f64;
@@ -362,9 +1193,12 @@ impl Keyboard {
             .key_slots
             .iter()
             .filter(|&(slot, key)| {
-                let invalid_location = slot.get_row() < TOP_ROW || slot.get_col() > R_PINKY;
+                // The swap table only covers the keys in the base layer's alpha area
+                let invalid_location = slot.get_row() < TOP_ROW
+                    || slot.get_row() > BOT_ROW
+                    || slot.get_col() > R_PINKY;
                 let static_key = self.valid_slots[key].len() == 1;
-                if invalid_location || static_key {
+                if invalid_location || static_key || *key == Key::BLANK {
                     return false;
                 }
 
@@ -376,7 +1210,13 @@ impl Keyboard {
             })
             .collect();
 
-        let select_a = select_key(&mut self.rng, &mut base_a, k_temp);
+        let weights_a: Vec<f64> = base_a
+            .iter()
+            .map(|(slot, key, _)| return swap_table.get_weight(slot, key))
+            .collect();
+        apply_confidence(&mut self.rng, &mut base_a, &weights_a);
+
+        let select_a = select_key(&mut self.rng, &mut base_a, k_temp, policy);
         let select_a_score = swap_table.get_score(&select_a.0, &select_a.1);
 
         let mut base_b: Vec<(Slot, Key, f64)> = self
@@ -385,11 +1225,13 @@ impl Keyboard {
             .filter(|&(slot_b, key_b)| {
                 let slot_a = select_a.0;
                 let key_a = select_a.1;
-                let invalid_slot = slot_b.get_row() < TOP_ROW || slot_b.get_col() > R_PINKY;
+                let invalid_slot = slot_b.get_row() < TOP_ROW
+                    || slot_b.get_row() > BOT_ROW
+                    || slot_b.get_col() > R_PINKY;
                 let bad_shuffle_check =
                     !shuffle_check(&self.valid_slots, slot_a, key_a, *slot_b, *key_b);
 
-                if bad_shuffle_check || invalid_slot {
+                if bad_shuffle_check || invalid_slot || *key_b == Key::BLANK {
                     return false;
                 }
 
@@ -409,23 +1251,79 @@ impl Keyboard {
             return false;
         }
 
-        let select_b = select_key(&mut self.rng, &mut base_b, k_temp);
+        // The new placements are the uncertain part of a swap, so the less tried of the two
+        // sets the confidence
+        let weights_b: Vec<f64> = base_b
+            .iter()
+            .map(|(slot_b, key_b, _)| {
+                let new_a = swap_table.get_weight(&select_a.0, key_b);
+                let new_b = swap_table.get_weight(slot_b, &select_a.1);
+                return new_a.min(new_b);
+            })
+            .collect();
+        apply_confidence(&mut self.rng, &mut base_b, &weights_b);
+
+        let select_b = select_key(&mut self.rng, &mut base_b, k_temp, policy);
         self.swap_keys(select_a.0, select_a.1, select_b.0, select_b.1);
 
         return true;
     }
 
+    // Alpha area, layer, and ISO slots holding a key that has more than one valid location
+    pub fn get_movable_slots(&self) -> Vec<(Slot, Key)> {
+        return self
+            .key_slots
+            .iter()
+            .filter(|&(slot, key)| {
+                let invalid_location =
+                    (slot.get_row() < TOP_ROW || slot.get_col() > R_PINKY) && !is_iso_slot(*slot);
+                let static_key = self.valid_slots[key].len() == 1;
+
+                return !invalid_location && !static_key;
+            })
+            .map(|(slot, key)| return (*slot, *key))
+            .collect();
+    }
+
+    // Every pair of movable slots whose keys can trade places under shuffle_check. Each pair is
+    // listed once
+    pub fn get_legal_swaps(&self) -> Vec<(Slot, Slot)> {
+        let movable = self.get_movable_slots();
+
+        let mut swaps: Vec<(Slot, Slot)> = Vec::new();
+        for (i, (slot_a, key_a)) in movable.iter().enumerate() {
+            for (slot_b, key_b) in movable.iter().skip(i + 1) {
+                if shuffle_check(&self.valid_slots, *slot_a, *key_a, *slot_b, *key_b) {
+                    swaps.push((*slot_a, *slot_b));
+                }
+            }
+        }
+
+        return swaps;
+    }
+
+    // The caller is responsible for checking that the swap is legal
+    pub fn apply_swap(&mut self, slot_a: Slot, slot_b: Slot) {
+        self.evaluated = false;
+        self.last_score = self.score;
+        self.score = 0.0_f64;
+
+        let key_a = self.key_slots[&slot_a];
+        let key_b = self.key_slots[&slot_b];
+        self.swap_keys(slot_a, key_a, slot_b, key_b);
+    }
+
     fn swap_keys(&mut self, slot_a: Slot, key_a: Key, slot_b: Slot, key_b: Key) {
         self.last_swap_a = (slot_a, key_a);
         self.last_swap_b = (slot_b, key_b);
 
         self.key_slots.insert(slot_a, key_b);
-        self.slot_ascii[usize::from(key_b.get_base())] = Some(slot_a);
-        self.slot_ascii[usize::from(key_b.get_shift())] = Some(slot_a);
+        self.slot_chars.insert(key_b.get_base(), slot_a);
+        self.slot_chars.insert(key_b.get_shift(), slot_a);
 
         self.key_slots.insert(slot_b, key_a);
-        self.slot_ascii[usize::from(key_a.get_base())] = Some(slot_b);
-        self.slot_ascii[usize::from(key_a.get_shift())] = Some(slot_b);
+        self.slot_chars.insert(key_a.get_base(), slot_b);
+        self.slot_chars.insert(key_a.get_shift(), slot_b);
     }
 
     // For any slot/key pair in the swap map, a higher weighted average means improvement has been
@@ -447,80 +1345,107 @@ impl Keyboard {
             return;
         }
 
-        let corpus = get_corpus();
-        self.score = 0.0_f64;
+        EVAL_CNT.fetch_add(1, Ordering::Relaxed);
+
+        let sets = get_corpus_sets();
+        self.score = if sets.is_empty() {
+            self.eval_entries(get_corpus())
+        } else {
+            let ratios = self.get_corpus_ratios();
+            combine_corpus_scores(&ratios)
+        };
+
+        self.evaluated = true;
+    }
+
+    // Each corpus's score as a share of Qwerty's on that corpus
+    fn get_corpus_ratios(&mut self) -> Vec<f64> {
+        return get_corpus_sets()
+            .iter()
+            .zip(get_qwerty_baselines())
+            .map(|(set, baseline)| return self.eval_entries(set.get_entries()) / baseline)
+            .collect();
+    }
+
+    // For display. Empty unless corpus.sets is given
+    pub fn get_corpus_scores(&mut self) -> Vec<f64> {
+        if get_corpus_sets().is_empty() {
+            return Vec::new();
+        }
+
+        return self
+            .get_corpus_ratios()
+            .iter()
+            .map(|ratio| return ratio * QWERTY_PCT)
+            .collect();
+    }
+
+    // The hand balance and finger load are judged within the entries, so each corpus is held to
+    // them on its own
+    fn eval_entries(&mut self, entries: &[String]) -> f64 {
+        let mut score = 0.0_f64;
         self.last_slot_idx = None;
         self.prev_slot_idx = None;
         self.left_uses = 0.0_f64;
         self.right_uses = 0.0_f64;
-
-        for entry in corpus {
-            for b in entry.as_bytes() {
-                let this_key: Slot = if let Some(&Some(key)) = self.slot_ascii.get(usize::from(*b))
-                {
+        self.finger_uses = [0.0_f64; FINGER_CNT];
+
+        // Finger movement is scored on the physical key, so history carries across layer switches
+        // Dead key sequences were already expanded into their key presses by initialize_corpus
+        let mut last_layer: usize = 0;
+        for entry in entries {
+            for c in entry.chars() {
+                let this_key: Slot = if let Some(key) = self.slot_chars.get(c) {
                     key
                 } else {
                     self.prev_slot_idx = self.last_slot_idx;
                     self.last_slot_idx = None;
+                    last_layer = 0;
                     continue;
                 };
 
-                self.score += self.get_efficiency(this_key);
+                let layer = this_key.get_layer();
+                let phys_key = this_key.get_phys();
+                score += self.get_efficiency(phys_key) * get_layer_mult(layer, last_layer);
+                last_layer = layer;
 
                 self.prev_slot_idx = self.last_slot_idx;
-                self.last_slot_idx = Some(this_key);
+                self.last_slot_idx = Some(phys_key);
             }
         }
 
         if self.left_uses < self.right_uses {
-            self.score *= self.left_uses / self.right_uses;
+            score *= self.left_uses / self.right_uses;
         } else {
-            self.score *= self.right_uses / self.left_uses;
+            score *= self.right_uses / self.left_uses;
         }
 
-        self.evaluated = true;
-    }
-
-    // NOTE: A single major efficiency penalty at any point in the algorithm can cause the entire
-    // layout to change. Be careful over-indexing for any particular factor
-    fn get_efficiency(&mut self, this_slot: Slot) -> f64 {
-        let mut eff = BASE_EFF;
-
-        let this_hand = Hand::from_slot(this_slot);
-        if this_hand == Hand::Right {
-            self.right_uses += 1.0_f64;
-        } else {
-            self.left_uses += 1.0_f64;
+        if let Some(targets) = &get_config().fingers.load_targets {
+            score *= get_load_mult(&self.finger_uses, targets);
         }
 
-        eff *= global_adjustments(this_slot);
+        return score;
+    }
 
-        let last_compare: Option<KeyCompare> = self
-            .last_slot_idx
-            .map(|last_slot| return compare_slots(this_slot, last_slot, true));
-        if let Some(key_compare) = last_compare {
-            match key_compare {
-                KeyCompare::Mult(x) => return eff * x,
-                KeyCompare::Mismatch => {}
-            }
-        }
+    // Thumb keys are left out of the hand balance. Space alone would swamp whichever side has it
+    fn get_efficiency(&mut self, this_slot: Slot) -> f64 {
+        let finger_id = get_finger_id(this_slot);
+        self.finger_uses[finger_id.get_index()] += 1.0_f64;
 
-        let prev_compare: Option<KeyCompare> = self
-            .prev_slot_idx
-            .map(|prev_slot| return compare_slots(this_slot, prev_slot, false));
-        if let Some(key_compare) = prev_compare {
-            match key_compare {
-                KeyCompare::Mult(x) => return eff * x,
-                KeyCompare::Mismatch => {}
+        let this_hand = finger_id.get_hand();
+        if finger_id.get_finger() != Finger::Thumb {
+            if this_hand == Hand::Right {
+                self.right_uses += 1.0_f64;
+            } else {
+                self.left_uses += 1.0_f64;
             }
         }
 
-        eff *= check_key_no_hist(this_slot);
-
-        return eff;
+        return get_key_eff(this_slot, self.last_slot_idx, self.prev_slot_idx);
     }
 
     // FUTURE: Very inefficient
+    // FUTURE: Layers are not shown
     pub fn get_display_chars(&self) -> Vec<Vec<char>> {
         let mut num_row: Vec<char> = Vec::new();
         let mut top_row: Vec<char> = Vec::new();
@@ -529,13 +1454,13 @@ impl Keyboard {
 
         for (slot, key) in &self.key_slots {
             if slot.get_row() == 0 {
-                num_row.push(char::from(key.get_base()));
+                num_row.push(key.get_base());
             } else if slot.get_row() == 1 {
-                top_row.push(char::from(key.get_base()));
+                top_row.push(key.get_base());
             } else if slot.get_row() == 2 {
-                home_row.push(char::from(key.get_base()));
+                home_row.push(key.get_base());
             } else if slot.get_row() == 3 {
-                bot_row.push(char::from(key.get_base()));
+                bot_row.push(key.get_base());
             }
         }
 
@@ -543,10 +1468,75 @@ impl Keyboard {
         return display_chars;
     }
 
+    // Number of slots holding a different key. Static keys never move, so only the swappable
+    // area contributes
+    pub fn distance(&self, other: &Keyboard) -> usize {
+        return self
+            .key_slots
+            .iter()
+            .filter(|(slot, key)| return other.key_slots.get(slot) != Some(key))
+            .count();
+    }
+
+    // Average straight-line displacement, in key widths, of each key between the two layouts.
+    // Keys are weighted by how often their characters appear in the corpus, so moving E counts for
+    // far more than moving Q
+    pub fn weighted_distance(&self, other: &Keyboard) -> f64 {
+        let char_freqs = get_char_freqs();
+
+        let mut total_dist: f64 = 0.0;
+        let mut total_weight: f64 = 0.0;
+        for (slot, key) in &self.key_slots {
+            let Some(other_slot) = other.get_char_slot(key.get_base()) else {
+                continue;
+            };
+
+            let get_freq = |c: char| return char_freqs.get(&c).copied().unwrap_or(0.0_f64);
+            let weight = get_freq(key.get_base()) + get_freq(key.get_shift());
+            let row_diff = slot.get_row().abs_diff(other_slot.get_row()) as f64;
+            let col_diff = slot.get_col().abs_diff(other_slot.get_col()) as f64;
+
+            total_dist += weight * row_diff.hypot(col_diff);
+            total_weight += weight;
+        }
+
+        if total_weight <= 0.0 {
+            return 0.0;
+        }
+
+        return total_dist / total_weight;
+    }
+
     pub fn get_score(&self) -> f64 {
         return self.score;
     }
 
+    pub fn get_key_slots(&self) -> &BTreeMap<Slot, Key> {
+        return &self.key_slots;
+    }
+
+    pub fn get_valid_slots(&self) -> &BTreeMap<Key, Vec<Slot>> {
+        return &self.valid_slots;
+    }
+
+    // The operator this keyboard was bred with, if any
+    pub fn get_crossover(&self) -> Option<CrossoverOp> {
+        return self.crossover;
+    }
+
+    // Checks the thumb keys as well as the main layout
+    pub fn get_slot_key(&self, slot: Slot) -> Option<Key> {
+        return self
+            .key_slots
+            .get(&slot)
+            .or_else(|| return get_thumb_keys().get(&slot))
+            .copied();
+    }
+
+    pub fn get_char_slot(&self, c: char) -> Option<Slot> {
+        return self.slot_chars.get(c);
+    }
+
     pub fn get_generation(&self) -> usize {
         return self.generation;
     }
@@ -579,3 +1569,52 @@ impl Keyboard {
         }
     }
 }
+
+// Which slot types each character. Nearly every corpus character is ASCII, so those are looked up
+// by index, and anything else goes through a map
+#[derive(Clone)]
+struct CharSlots {
+    ascii: Vec<Option<Slot>>,
+    other: BTreeMap<char, Slot>,
+}
+
+impl CharSlots {
+    fn new() -> Self {
+        return Self {
+            ascii: vec![None; ASCII_CNT],
+            other: BTreeMap::new(),
+        };
+    }
+
+    fn insert(&mut self, c: char, slot: Slot) {
+        if c.is_ascii() {
+            self.ascii[c as usize] = Some(slot);
+        } else {
+            self.other.insert(c, slot);
+        }
+    }
+
+    // PERF: Called for every corpus character in eval
+    fn get(&self, c: char) -> Option<Slot> {
+        if c.is_ascii() {
+            return self.ascii[c as usize];
+        }
+
+        return self.other.get(&c).copied();
+    }
+}
+
+// Thumb keys are added last, so a static key moved to a thumb is typed there instead
+fn get_char_slots(key_slots: &BTreeMap<Slot, Key>) -> CharSlots {
+    let mut slot_chars = CharSlots::new();
+    for (slot, key) in key_slots {
+        slot_chars.insert(key.get_base(), *slot);
+        slot_chars.insert(key.get_shift(), *slot);
+    }
+
+    for (slot, key) in get_thumb_keys() {
+        slot_chars.insert(key.get_base(), *slot);
+    }
+
+    return slot_chars;
+}
@@ -0,0 +1,190 @@
+extern crate alloc;
+
+use {
+    alloc::collections::{BTreeMap, BTreeSet},
+    core::fmt::Write as _,
+    std::{fs, path::Path},
+};
+
+use anyhow::{Result, anyhow};
+
+use crate::{
+    kb_builders::{
+        check_col, get_charset_key, get_valid_key_locs_sorted, move_to_iso, move_to_layers,
+    },
+    keyboard::{BOT_ROW, Keyboard, LAYER_ROW, MAX_LAYERS, NUM_ROW, TOP_ROW},
+    structs::{Key, Slot},
+};
+
+// Starts each layer's rows, as in "layer 1"
+const LAYER_MARKER: &str = "layer";
+
+const HEADER: &str = "# ma_keyboard layout";
+
+// Layout files hold one keyboard row per line, starting with the number row. Each key is written
+// as its unshifted character, separated by spaces. Newline and backslash are escaped as \n and \\.
+// With layers on, the number row is left out, and each layer follows as a "layer N" line and its
+// three rows. Empty layer slots are written as \0. On an ISO board, the home row ends with
+// backslash and Enter, and the ISO key ends the bottom row, blank or not
+pub fn save_layout(kb: &Keyboard, path: &Path) -> Result<()> {
+    let mut contents = String::new();
+    let _ = writeln!(contents, "{HEADER}");
+
+    let mut last_slot: Option<Slot> = None;
+    for (slot, key) in kb.get_key_slots() {
+        match last_slot {
+            Some(last) if last.get_row() == slot.get_row() => contents.push(' '),
+            Some(_) => contents.push('\n'),
+            None => {}
+        }
+
+        // Layer rows sort after the base rows, so each layer starts once
+        let layer = slot.get_layer();
+        if layer > 0 && last_slot.is_none_or(|l| return l.get_layer() != layer) {
+            let _ = writeln!(contents, "{LAYER_MARKER} {layer}");
+        }
+
+        contents.push_str(&escape_key(key.get_base()));
+        last_slot = Some(*slot);
+    }
+
+    contents.push('\n');
+    fs::write(path, contents)?;
+
+    return Ok(());
+}
+
+/// Accepts either a built-in layout name (qwerty, dvorak) or the path to a layout file.
+pub fn load_layout(spec: &str) -> Result<Keyboard> {
+    if let Some(kb) = Keyboard::from_name(spec) {
+        return Ok(kb);
+    }
+
+    let path = Path::new(spec);
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            let err_string = format!("Unable to open layout {} -- {}", path.display(), e);
+            return Err(anyhow!(err_string));
+        }
+    };
+
+    let known_keys: BTreeMap<char, Key> = get_valid_key_locs_sorted()
+        .into_iter()
+        .map(|(key, _)| return (key.get_base(), key))
+        .collect();
+
+    let mut key_slots: BTreeMap<Slot, Key> = BTreeMap::new();
+    let mut seen: BTreeSet<Key> = BTreeSet::new();
+    let lines: Vec<&str> = contents
+        .lines()
+        .map(str::trim)
+        .filter(|l| return !l.is_empty() && !l.starts_with('#'))
+        .collect();
+
+    // A layered file has no number row
+    let is_layered = lines.iter().any(|l| return l.starts_with(LAYER_MARKER));
+    let mut layer: usize = 0;
+    let mut row = if is_layered {
+        TOP_ROW
+    } else {
+        NUM_ROW
+    };
+
+    for line in lines {
+        if let Some(layer_str) = line.strip_prefix(LAYER_MARKER) {
+            layer = match layer_str.trim().parse::<usize>() {
+                Ok(n) if (1..=MAX_LAYERS).contains(&n) => n,
+                _ => return Err(anyhow!("Invalid layer \"{line}\" in {}", path.display())),
+            };
+            row = TOP_ROW;
+            continue;
+        }
+
+        for (col, token) in line.split_whitespace().enumerate() {
+            if row > BOT_ROW
+                || (layer == 0 && !check_col(row, col))
+                || (layer > 0 && !check_col(LAYER_ROW, col))
+            {
+                return Err(anyhow!("Too many keys in row {row} of {}", path.display()));
+            }
+
+            // A key replaced by charset.keys is read as its replacement, so files saved without
+            // the character set still load
+            let mut base = unescape_key(token)?;
+            if let Some(charset_key) = get_charset_key(base) {
+                base = charset_key.get_base();
+            }
+
+            let Some(key) = known_keys.get(&base) else {
+                return Err(anyhow!("Unknown key \"{token}\" in {}", path.display()));
+            };
+
+            if *key != Key::BLANK && !seen.insert(*key) {
+                return Err(anyhow!("Key \"{token}\" used twice in {}", path.display()));
+            }
+
+            let slot = if layer == 0 {
+                Slot::from_tuple((row, col))
+            } else {
+                Slot::from_layer(layer, row, col)
+            };
+            key_slots.insert(slot, *key);
+        }
+
+        row += 1;
+    }
+
+    // Layouts saved without layers can still be loaded when layers are on, and ANSI layouts can
+    // be loaded on an ISO board
+    move_to_layers(&mut key_slots);
+    move_to_iso(&mut key_slots);
+
+    let reference = Keyboard::create_qwerty();
+    let expected_slots: Vec<&Slot> = reference.get_key_slots().keys().collect();
+    let found_slots: Vec<&Slot> = key_slots.keys().collect();
+    let key_cnt = known_keys
+        .values()
+        .filter(|k| return **k != Key::BLANK)
+        .count();
+    if expected_slots != found_slots || seen.len() != key_cnt {
+        return Err(anyhow!(
+            "{} does not match the keyboard geometry. Rows must have 12, 13, 12, and 10 keys (12, \
+             12, 13, and 11 on an ISO board), or 10 keys each with layers on, with each \
+             layer.count layer filled",
+            path.display()
+        ));
+    }
+
+    return Ok(Keyboard::from_key_slots(key_slots));
+}
+
+// How compare and report name a key. Blank slots are left unlabelled
+pub fn key_label(base: char) -> String {
+    return match base {
+        '\n' => "Enter".to_string(),
+        ' ' => "Space".to_string(),
+        '\0' => String::new(),
+        _ => base.to_string(),
+    };
+}
+
+pub fn escape_key(base: char) -> String {
+    return match base {
+        '\n' => "\\n".to_string(),
+        '\\' => "\\\\".to_string(),
+        '\0' => "\\0".to_string(),
+        _ => base.to_string(),
+    };
+}
+
+pub fn unescape_key(token: &str) -> Result<char> {
+    let mut chars = token.chars();
+    return match (token, chars.next(), chars.next()) {
+        ("\\n", ..) => Ok('\n'),
+        ("\\\\", ..) => Ok('\\'),
+        ("\\0", ..) => Ok('\0'),
+        (_, Some(c), None) => Ok(c),
+        _ => Err(anyhow!("Invalid key token \"{token}\"")),
+    };
+}
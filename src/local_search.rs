@@ -0,0 +1,381 @@
+extern crate alloc;
+
+use {
+    alloc::collections::VecDeque,
+    core::{fmt, str::FromStr},
+};
+
+use {
+    anyhow::{Error, anyhow},
+    rand::{Rng as _, rngs::SmallRng},
+};
+
+use crate::{config::get_config, keyboard::Keyboard, population::Population, structs::Slot};
+
+// Climbs a single keyboard using the population's swap table and climbing state. The returned
+// keyboard must never score lower than the one passed in, since elites are climbed too
+pub trait LocalSearch {
+    fn climb(&self, population: &mut Population, keyboard: Keyboard) -> Keyboard;
+}
+
+// Evolvable choice of local search. Tuning values that are not part of the gene (step counts,
+// temperatures, tenure) come from the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocalSearchMethod {
+    Plateau,
+    Annealing(CoolingSchedule),
+    Tabu,
+    Steepest,
+}
+
+impl LocalSearchMethod {
+    pub fn random(rng: &mut SmallRng) -> Self {
+        return match rng.random_range(0..4) {
+            0 => LocalSearchMethod::Plateau,
+            1 => LocalSearchMethod::Annealing(CoolingSchedule::random(rng)),
+            2 => LocalSearchMethod::Tabu,
+            _ => LocalSearchMethod::Steepest,
+        };
+    }
+}
+
+impl LocalSearch for LocalSearchMethod {
+    fn climb(&self, population: &mut Population, keyboard: Keyboard) -> Keyboard {
+        return match self {
+            LocalSearchMethod::Plateau => PlateauClimber.climb(population, keyboard),
+            LocalSearchMethod::Annealing(schedule) => Annealer {
+                schedule: *schedule,
+            }
+            .climb(population, keyboard),
+            LocalSearchMethod::Tabu => TabuSearch.climb(population, keyboard),
+            LocalSearchMethod::Steepest => SteepestAscent.climb(population, keyboard),
+        };
+    }
+}
+
+impl fmt::Display for LocalSearchMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            LocalSearchMethod::Plateau => write!(f, "Plateau"),
+            LocalSearchMethod::Annealing(schedule) => write!(f, "Annealing ({schedule})"),
+            LocalSearchMethod::Tabu => write!(f, "Tabu"),
+            LocalSearchMethod::Steepest => write!(f, "Steepest"),
+        };
+    }
+}
+
+// The cooling schedule is optional and given after a colon (annealing:linear)
+impl FromStr for LocalSearchMethod {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        let (name, schedule) = match lower.split_once(':') {
+            Some((before, after)) => (before.trim(), Some(after.trim())),
+            None => (lower.as_str(), None),
+        };
+
+        return match (name, schedule) {
+            ("plateau", None) => Ok(LocalSearchMethod::Plateau),
+            ("annealing", None) => Ok(LocalSearchMethod::Annealing(CoolingSchedule::Exponential)),
+            ("annealing", Some(raw_schedule)) => {
+                Ok(LocalSearchMethod::Annealing(raw_schedule.parse()?))
+            }
+            ("tabu", None) => Ok(LocalSearchMethod::Tabu),
+            ("steepest", None) => Ok(LocalSearchMethod::Steepest),
+            _ => Err(anyhow!(
+                "expected plateau, annealing[:schedule], tabu, or steepest"
+            )),
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CoolingSchedule {
+    Linear,
+    Exponential,
+    Logarithmic,
+}
+
+impl CoolingSchedule {
+    fn random(rng: &mut SmallRng) -> Self {
+        return match rng.random_range(0..3) {
+            0 => CoolingSchedule::Linear,
+            1 => CoolingSchedule::Exponential,
+            _ => CoolingSchedule::Logarithmic,
+        };
+    }
+
+    // Linear and exponential cooling both finish the run at (or near) zero. Logarithmic cooling
+    // never gets there and keeps accepting some worse swaps to the end
+    fn get_temp(self, start_temp: f64, step: usize, steps: usize) -> f64 {
+        const EXP_END_RATIO: f64 = 0.001;
+
+        let progress = step as f64 / steps.max(1) as f64;
+        return match self {
+            CoolingSchedule::Linear => start_temp * (1.0 - progress),
+            CoolingSchedule::Exponential => start_temp * EXP_END_RATIO.powf(progress),
+            CoolingSchedule::Logarithmic => start_temp / (core::f64::consts::E + step as f64).ln(),
+        };
+    }
+}
+
+impl fmt::Display for CoolingSchedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            CoolingSchedule::Linear => write!(f, "Linear"),
+            CoolingSchedule::Exponential => write!(f, "Exp."),
+            CoolingSchedule::Logarithmic => write!(f, "Log."),
+        };
+    }
+}
+
+impl FromStr for CoolingSchedule {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "linear" => Ok(CoolingSchedule::Linear),
+            "exponential" => Ok(CoolingSchedule::Exponential),
+            "logarithmic" => Ok(CoolingSchedule::Logarithmic),
+            _ => Err(anyhow!(
+                "expected a cooling schedule of linear, exponential, or logarithmic"
+            )),
+        };
+    }
+}
+
+// Greedy climber. Only improvements are kept, and the climb ends once the decayed weighted
+// average of recent improvements falls below the overall average
+pub struct PlateauClimber;
+
+impl LocalSearch for PlateauClimber {
+    // NOTE: Changing one key at a time works best. If you change two keys, the algorithm will find
+    // bigger changes less frequently. This causes the decay to continue for about as many
+    // iterations as it would if doing only one step, but fewer improvements will be found, causing
+    // the improvement at the end of the hill climbing step to be lower
+    fn climb(&self, population: &mut Population, keyboard: Keyboard) -> Keyboard {
+        let mut last_improvement: f64 = 0.0;
+        let mut avg_improvement: f64 = 0.0;
+        let mut weighted_avg: f64 = 0.0;
+        let mut sum_weights: f64 = 0.0;
+
+        let mut kb = keyboard;
+
+        for i in 1..=100_000 {
+            let mut climb_kb = population
+                .table_step(&kb)
+                .unwrap_or_else(|| return kb.kb_clone());
+
+            let this_improvement = (climb_kb.get_score() - kb.get_score()).max(0.0);
+            avg_improvement = get_new_avg(this_improvement, avg_improvement, i);
+
+            let improvement_delta = this_improvement - last_improvement;
+            last_improvement = this_improvement;
+
+            let this_weight = get_weight(improvement_delta);
+            sum_weights *= population.get_climb_decay();
+            let inflated_w_avg = weighted_avg * sum_weights;
+            sum_weights += this_weight;
+            weighted_avg = (inflated_w_avg + this_improvement * this_weight) / sum_weights;
+
+            if climb_kb.get_score() > kb.get_score() {
+                climb_kb.add_pos_iter();
+                kb = climb_kb;
+            }
+
+            // Check i > 1 to paste over an edge case where the first improvement on the first
+            // iteration is smaller than the unweighted mean due to floating point imprecision
+            let plateauing: bool = weighted_avg < avg_improvement && i > 1;
+            let not_starting: bool =
+                avg_improvement <= 0.0 && i as f64 >= population.get_avg_climb_iter();
+            if plateauing || not_starting {
+                population.record_climb(i);
+
+                break;
+            }
+        }
+
+        return kb;
+    }
+}
+
+// Worse swaps are accepted with a probability that shrinks as the temperature cools. The score
+// change is taken relative to the current score, so the temperature does not depend on corpus size
+pub struct Annealer {
+    schedule: CoolingSchedule,
+}
+
+impl LocalSearch for Annealer {
+    fn climb(&self, population: &mut Population, keyboard: Keyboard) -> Keyboard {
+        let config = &get_config().local_search;
+
+        let mut best = keyboard.kb_clone();
+        let mut current = keyboard;
+        let mut steps_taken: usize = 0;
+
+        for step in 0..config.anneal_steps {
+            let Some(mut candidate) = population.table_step(&current) else {
+                break;
+            };
+            steps_taken += 1;
+
+            let temp = self
+                .schedule
+                .get_temp(config.anneal_temp, step, config.anneal_steps);
+            let delta = (candidate.get_score() - current.get_score())
+                / current.get_score().max(f64::EPSILON);
+
+            let accept = if delta >= 0.0 {
+                true
+            } else if temp > 0.0 {
+                population.get_rng().random::<f64>() < (delta / temp).exp()
+            } else {
+                false
+            };
+
+            if !accept {
+                continue;
+            }
+
+            if delta > 0.0 {
+                candidate.add_pos_iter();
+            }
+
+            current = candidate;
+            if current.get_score() > best.get_score() {
+                best = current.kb_clone();
+            }
+        }
+
+        population.record_climb(steps_taken);
+        return best;
+    }
+}
+
+// Each step samples swaps from the swap table and moves to the best one, even if it is worse.
+// Recently swapped slot pairs are tabu unless they would beat the best keyboard found
+pub struct TabuSearch;
+
+impl LocalSearch for TabuSearch {
+    fn climb(&self, population: &mut Population, keyboard: Keyboard) -> Keyboard {
+        let config = &get_config().local_search;
+
+        let mut best = keyboard.kb_clone();
+        let mut current = keyboard;
+        let mut tabu: VecDeque<(Slot, Slot)> = VecDeque::with_capacity(config.tabu_tenure + 1);
+        let mut stale_steps: usize = 0;
+        let mut evals: usize = 0;
+
+        while stale_steps < config.tabu_stale_steps {
+            let mut next: Option<Keyboard> = None;
+            for _ in 0..config.tabu_sample {
+                let Some(candidate) = population.table_step(&current) else {
+                    break;
+                };
+                evals += 1;
+
+                let (slot_a, _, slot_b, _, _) = candidate.get_last_swap_info();
+                let is_tabu = tabu.contains(&(slot_a, slot_b)) || tabu.contains(&(slot_b, slot_a));
+                if is_tabu && candidate.get_score() <= best.get_score() {
+                    continue;
+                }
+
+                if next
+                    .as_ref()
+                    .is_none_or(|n| return candidate.get_score() > n.get_score())
+                {
+                    next = Some(candidate);
+                }
+            }
+
+            let Some(mut moved) = next else {
+                stale_steps += 1;
+                continue;
+            };
+
+            let (slot_a, _, slot_b, _, _) = moved.get_last_swap_info();
+            tabu.push_back((slot_a, slot_b));
+            if tabu.len() > config.tabu_tenure {
+                tabu.pop_front();
+            }
+
+            if moved.get_score() > current.get_score() {
+                moved.add_pos_iter();
+            }
+
+            current = moved;
+            if current.get_score() > best.get_score() {
+                best = current.kb_clone();
+                stale_steps = 0;
+            } else {
+                stale_steps += 1;
+            }
+        }
+
+        population.record_climb(evals);
+        return best;
+    }
+}
+
+// Evaluates every legal swap and takes the best, until no swap improves the keyboard. The result
+// is a true local optimum over 2-swaps
+// PERF: Every step is a full corpus evaluation for each legal swap
+pub struct SteepestAscent;
+
+impl LocalSearch for SteepestAscent {
+    fn climb(&self, population: &mut Population, keyboard: Keyboard) -> Keyboard {
+        let mut kb = keyboard;
+        let mut evals: usize = 0;
+
+        loop {
+            let mut next: Option<Keyboard> = None;
+            for (slot_a, slot_b) in kb.get_legal_swaps() {
+                let mut candidate = kb.kb_clone();
+                candidate.apply_swap(slot_a, slot_b);
+                candidate.eval();
+                population.record_swap(&candidate);
+                evals += 1;
+
+                let to_beat = next.as_ref().map_or(kb.get_score(), Keyboard::get_score);
+                if candidate.get_score() > to_beat {
+                    next = Some(candidate);
+                }
+            }
+
+            let Some(mut improved) = next else {
+                break;
+            };
+
+            improved.add_pos_iter();
+            kb = improved;
+        }
+
+        population.record_climb(evals);
+        return kb;
+    }
+}
+
+pub fn get_new_avg(new_value: f64, old_avg: f64, new_count: usize) -> f64 {
+    let new_value_for_new_avg: f64 = new_value / (new_count as f64);
+    let old_avg_for_new_avg: f64 = old_avg * ((new_count as f64 - 1.0) / new_count as f64);
+
+    return new_value_for_new_avg + old_avg_for_new_avg;
+}
+
+// The strong weight toward positive iterations is to give hill climbers the chance to catch up in
+// later generations
+fn get_weight(delta: f64) -> f64 {
+    const K: f64 = 0.01;
+
+    if delta <= 0.0_f64 {
+        return 1.0;
+    }
+
+    return 1.0 + K * delta.powf(0.9);
+
+    // Alternatives:
+    // return 1.0 + K * delta.sqrt();
+    // return 1.0 + K * delta.ln();
+    // return 1.0 + K * delta.powf(0.0001);
+}
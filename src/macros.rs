@@ -2,7 +2,7 @@
 macro_rules! most_rows {
     () => {
         pub const NUM_ROW: usize = 0;
-        const TOP_ROW: usize = 1;
+        pub const TOP_ROW: usize = 1;
         pub const BOT_ROW: usize = 3;
     };
 }
@@ -33,6 +33,38 @@ macro_rules! rows {
     };
 }
 
+// Split and ergonomic boards put a few keys under each thumb. Thumb slots sit on their own row,
+// reusing the inner columns so the hand can be read from the column as usual. The home key is
+// nearest the centre and the reach key is one step toward the palm
+#[macro_export]
+macro_rules! thumb_row {
+    () => {
+        pub const THUMB_ROW: usize = 4;
+        const L_THUMB_REACH: usize = 3;
+        const R_THUMB_REACH: usize = 6;
+    };
+}
+
+// Each layer is a copy of the top, home, and bottom rows of the alpha area, reached by holding a
+// thumb key. Layer slots sit on their own rows after the thumb row, three per layer
+#[macro_export]
+macro_rules! layer_rows {
+    () => {
+        pub const LAYER_ROW: usize = 5;
+        pub const MAX_LAYERS: usize = 2;
+    };
+}
+
+#[macro_export]
+macro_rules! thumb_cluster {
+    () => {
+        thumb_row!();
+
+        const L_THUMB: usize = 4;
+        const R_THUMB: usize = 5;
+    };
+}
+
 #[macro_export]
 macro_rules! edge_cols {
     () => {
@@ -76,59 +108,59 @@ macro_rules! cols {
 #[macro_export]
 macro_rules! keys {
     () => {
-        const COMMA: (u8, u8) = (b',', b'<');
-        const PERIOD: (u8, u8) = (b'.', b'>');
-        const SEMICOLON: (u8, u8) = (b';', b':');
-        const QUOTE: (u8, u8) = (b'\'', b'"');
-        const A: (u8, u8) = (b'a', b'A');
-        const B: (u8, u8) = (b'b', b'B');
-        const C: (u8, u8) = (b'c', b'C');
-        const D: (u8, u8) = (b'd', b'D');
-        const E: (u8, u8) = (b'e', b'E');
-        const F: (u8, u8) = (b'f', b'F');
-        const G: (u8, u8) = (b'g', b'G');
-        const H: (u8, u8) = (b'h', b'H');
-        const I: (u8, u8) = (b'i', b'I');
-        const J: (u8, u8) = (b'j', b'J');
-        const K: (u8, u8) = (b'k', b'K');
-        const L: (u8, u8) = (b'l', b'L');
-        const M: (u8, u8) = (b'm', b'M');
-        const N: (u8, u8) = (b'n', b'N');
-        const O: (u8, u8) = (b'o', b'O');
-        const P: (u8, u8) = (b'p', b'P');
-        const Q: (u8, u8) = (b'q', b'Q');
-        const R: (u8, u8) = (b'r', b'R');
-        const S: (u8, u8) = (b's', b'S');
-        const T: (u8, u8) = (b't', b'T');
-        const U: (u8, u8) = (b'u', b'U');
-        const V: (u8, u8) = (b'v', b'V');
-        const W: (u8, u8) = (b'w', b'W');
-        const X: (u8, u8) = (b'x', b'X');
-        const Y: (u8, u8) = (b'y', b'Y');
-        const Z: (u8, u8) = (b'z', b'Z');
+        const COMMA: (char, char) = (',', '<');
+        const PERIOD: (char, char) = ('.', '>');
+        const SEMICOLON: (char, char) = (';', ':');
+        const QUOTE: (char, char) = ('\'', '"');
+        const A: (char, char) = ('a', 'A');
+        const B: (char, char) = ('b', 'B');
+        const C: (char, char) = ('c', 'C');
+        const D: (char, char) = ('d', 'D');
+        const E: (char, char) = ('e', 'E');
+        const F: (char, char) = ('f', 'F');
+        const G: (char, char) = ('g', 'G');
+        const H: (char, char) = ('h', 'H');
+        const I: (char, char) = ('i', 'I');
+        const J: (char, char) = ('j', 'J');
+        const K: (char, char) = ('k', 'K');
+        const L: (char, char) = ('l', 'L');
+        const M: (char, char) = ('m', 'M');
+        const N: (char, char) = ('n', 'N');
+        const O: (char, char) = ('o', 'O');
+        const P: (char, char) = ('p', 'P');
+        const Q: (char, char) = ('q', 'Q');
+        const R: (char, char) = ('r', 'R');
+        const S: (char, char) = ('s', 'S');
+        const T: (char, char) = ('t', 'T');
+        const U: (char, char) = ('u', 'U');
+        const V: (char, char) = ('v', 'V');
+        const W: (char, char) = ('w', 'W');
+        const X: (char, char) = ('x', 'X');
+        const Y: (char, char) = ('y', 'Y');
+        const Z: (char, char) = ('z', 'Z');
     };
 }
 
 #[macro_export]
 macro_rules! static_keys {
     () => {
-        const NEWLINE: (u8, u8) = (b'\n', b'\n');
-        const BACKSLASH: (u8, u8) = (b'\\', b'|');
-        const ONE: (u8, u8) = (b'1', b'!');
-        const TWO: (u8, u8) = (b'2', b'@');
-        const THREE: (u8, u8) = (b'3', b'#');
-        const FOUR: (u8, u8) = (b'4', b'$');
-        const FIVE: (u8, u8) = (b'5', b'%');
-        const SIX: (u8, u8) = (b'6', b'^');
-        const SEVEN: (u8, u8) = (b'7', b'&');
-        const EIGHT: (u8, u8) = (b'8', b'*');
-        const NINE: (u8, u8) = (b'9', b'(');
-        const ZERO: (u8, u8) = (b'0', b')');
-        const L_BRACKET: (u8, u8) = (b'[', b'{');
-        const R_BRACKET: (u8, u8) = (b']', b'}');
-        const DASH: (u8, u8) = (b'-', b'_');
-        const EQUALS: (u8, u8) = (b'=', b'+');
-        const F_SLASH: (u8, u8) = (b'/', b'?');
+        const NEWLINE: (char, char) = ('\n', '\n');
+        const BACKSLASH: (char, char) = ('\\', '|');
+        const ONE: (char, char) = ('1', '!');
+        const TWO: (char, char) = ('2', '@');
+        const THREE: (char, char) = ('3', '#');
+        const FOUR: (char, char) = ('4', '$');
+        const FIVE: (char, char) = ('5', '%');
+        const SIX: (char, char) = ('6', '^');
+        const SEVEN: (char, char) = ('7', '&');
+        const EIGHT: (char, char) = ('8', '*');
+        const NINE: (char, char) = ('9', '(');
+        const ZERO: (char, char) = ('0', ')');
+        const L_BRACKET: (char, char) = ('[', '{');
+        const R_BRACKET: (char, char) = (']', '}');
+        const DASH: (char, char) = ('-', '_');
+        const EQUALS: (char, char) = ('=', '+');
+        const F_SLASH: (char, char) = ('/', '?');
     };
 }
 
@@ -137,7 +169,7 @@ macro_rules! swappable_keys {
     () => {
         keys!();
 
-        const SWAPPABLE_KEYS: [(u8, u8); 30] = [
+        const SWAPPABLE_KEYS: [(char, char); 30] = [
             COMMA, PERIOD, SEMICOLON, QUOTE, A, B, C, D, E, F, G, H, I, J, K, L, M, N, O, P, Q, R,
             S, T, U, V, W, X, Y, Z,
         ];
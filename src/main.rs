@@ -95,30 +95,46 @@
 #![allow(clippy::panic)]
 #![allow(clippy::panic_in_result_fn)]
 #![allow(clippy::redundant_else)]
-#![allow(clippy::similar_names)]
 #![allow(clippy::string_slice)]
 #![allow(clippy::too_many_lines)] // Encourages premature factoring
 #![allow(clippy::uninlined_format_args)]
 #![allow(clippy::unwrap_in_result)]
 #![allow(clippy::use_debug)]
 
+mod args;
+mod compare;
+mod config;
 mod corpus;
+mod crossover;
 mod display;
+mod distance;
+mod diversity;
 mod eval_funcs;
+mod export;
 mod kb_builders;
 mod keyboard;
+mod layout_file;
+mod local_search;
 mod macros;
 mod mapped_swap;
 mod meta_pop;
+mod migration;
+mod polish;
 mod pop_helpers;
 mod population;
+mod report;
+mod selection;
 mod setup;
+mod stopping;
 mod structs;
+mod swap_table_file;
+mod sweep;
+mod train;
 mod utils;
+mod variation;
 
 use std::{
     env,
-    fmt::format,
     fs::{self, File, OpenOptions},
     path::{Path, PathBuf},
     process::ExitCode,
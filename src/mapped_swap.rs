@@ -1,14 +1,58 @@
 extern crate alloc;
 
-use alloc::collections::BTreeMap;
+use {
+    alloc::collections::BTreeMap,
+    core::{cmp, fmt, str::FromStr},
+};
 
-use rand::{Rng as _, rngs::SmallRng};
+use {
+    anyhow::{Error, anyhow},
+    rand::{Rng as _, rngs::SmallRng},
+};
 
 use crate::{
+    config::get_config,
     population::SwapTable,
     structs::{Key, Slot},
+    variation::sample_normal,
 };
 
+// How the amount of evidence behind each swap table entry is used when picking candidates. Without
+// it, entries that were never tried sit at 0.0 and are only picked as often as that score allows
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Confidence {
+    None,
+    // Upper confidence bound. Entries with little weight get a bonus that shrinks as they are
+    // tried
+    Ucb,
+    // Each entry's score is drawn from a normal distribution around its average that narrows as
+    // weight builds
+    Thompson,
+}
+
+impl fmt::Display for Confidence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Confidence::None => write!(f, "None"),
+            Confidence::Ucb => write!(f, "UCB"),
+            Confidence::Thompson => write!(f, "Thompson"),
+        };
+    }
+}
+
+impl FromStr for Confidence {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "none" => Ok(Confidence::None),
+            "ucb" => Ok(Confidence::Ucb),
+            "thompson" => Ok(Confidence::Thompson),
+            _ => Err(anyhow!("expected none, ucb, or thompson")),
+        };
+    }
+}
+
 // PERF: valid_loc_a doesn't need to be checked when running a basic shuffle
 pub fn shuffle_check(
     valid_slots: &BTreeMap<Key, Vec<Slot>>,
@@ -53,20 +97,269 @@ pub fn select_key(
     rng: &mut SmallRng,
     values: &mut [(Slot, Key, f64)],
     k_temp: f64,
+    policy: SwapPolicy,
 ) -> (Slot, Key, f64) {
     debug_assert!(
         !values.is_empty(),
         "Should always be candidates in select_swap"
     );
 
-    apply_minmax(values);
-    let var = get_variance(values);
-    let temp = get_temp(var, k_temp);
-    apply_softmax(values, temp);
+    return policy.select(rng, values, k_temp);
+}
+
+// Picks one candidate from a set of swap table scores. Higher scores are better. Values may be
+// rewritten in place
+pub trait SelectionPolicy {
+    fn select(
+        &self,
+        rng: &mut SmallRng,
+        values: &mut [(Slot, Key, f64)],
+        k_temp: f64,
+    ) -> (Slot, Key, f64);
+}
+
+// Evolvable choice of selection policy. Tuning values that are not part of the gene (temperature,
+// epsilon, k) come from the config
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SwapPolicy {
+    VarianceSoftmax,
+    FixedSoftmax,
+    EpsilonGreedy,
+    Uniform,
+    Rank,
+    TopK,
+}
+
+impl SwapPolicy {
+    pub fn random(rng: &mut SmallRng) -> Self {
+        return match rng.random_range(0..6) {
+            0 => SwapPolicy::VarianceSoftmax,
+            1 => SwapPolicy::FixedSoftmax,
+            2 => SwapPolicy::EpsilonGreedy,
+            3 => SwapPolicy::Uniform,
+            4 => SwapPolicy::Rank,
+            _ => SwapPolicy::TopK,
+        };
+    }
+}
+
+impl SelectionPolicy for SwapPolicy {
+    fn select(
+        &self,
+        rng: &mut SmallRng,
+        values: &mut [(Slot, Key, f64)],
+        k_temp: f64,
+    ) -> (Slot, Key, f64) {
+        let config = &get_config().swap_policy;
+
+        return match self {
+            SwapPolicy::VarianceSoftmax => VarianceSoftmax.select(rng, values, k_temp),
+            SwapPolicy::FixedSoftmax => {
+                FixedSoftmax { temp: config.temp }.select(rng, values, k_temp)
+            }
+            SwapPolicy::EpsilonGreedy => EpsilonGreedy {
+                epsilon: config.epsilon,
+            }
+            .select(rng, values, k_temp),
+            SwapPolicy::Uniform => UniformPick.select(rng, values, k_temp),
+            SwapPolicy::Rank => RankPick.select(rng, values, k_temp),
+            SwapPolicy::TopK => TopK { k: config.top_k }.select(rng, values, k_temp),
+        };
+    }
+}
+
+impl fmt::Display for SwapPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            SwapPolicy::VarianceSoftmax => write!(f, "Var. Softmax"),
+            SwapPolicy::FixedSoftmax => write!(f, "Fixed Softmax"),
+            SwapPolicy::EpsilonGreedy => write!(f, "Eps. Greedy"),
+            SwapPolicy::Uniform => write!(f, "Uniform"),
+            SwapPolicy::Rank => write!(f, "Rank"),
+            SwapPolicy::TopK => write!(f, "Top-K"),
+        };
+    }
+}
+
+impl FromStr for SwapPolicy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "variance_softmax" => Ok(SwapPolicy::VarianceSoftmax),
+            "fixed_softmax" => Ok(SwapPolicy::FixedSoftmax),
+            "epsilon_greedy" => Ok(SwapPolicy::EpsilonGreedy),
+            "uniform" => Ok(SwapPolicy::Uniform),
+            "rank" => Ok(SwapPolicy::Rank),
+            "top_k" => Ok(SwapPolicy::TopK),
+            _ => Err(anyhow!(
+                "expected variance_softmax, fixed_softmax, epsilon_greedy, uniform, rank, or top_k"
+            )),
+        };
+    }
+}
+
+// Softmax over min-maxed scores. The temperature comes from the scores' variance and k_temp, so
+// the distribution sharpens when the candidates are clearly separated
+struct VarianceSoftmax;
+
+impl SelectionPolicy for VarianceSoftmax {
+    fn select(
+        &self,
+        rng: &mut SmallRng,
+        values: &mut [(Slot, Key, f64)],
+        k_temp: f64,
+    ) -> (Slot, Key, f64) {
+        apply_minmax(values);
+        let var = get_variance(values);
+        let temp = get_temp(var, k_temp);
+        apply_softmax(values, temp);
+
+        return mapped_roulette(rng, values);
+    }
+}
+
+// Softmax over min-maxed scores at a constant temperature
+struct FixedSoftmax {
+    temp: f64,
+}
+
+impl SelectionPolicy for FixedSoftmax {
+    fn select(
+        &self,
+        rng: &mut SmallRng,
+        values: &mut [(Slot, Key, f64)],
+        _k_temp: f64,
+    ) -> (Slot, Key, f64) {
+        apply_minmax(values);
+        apply_softmax(values, self.temp);
+
+        return mapped_roulette(rng, values);
+    }
+}
+
+// The best candidate, except a uniform pick with probability epsilon
+struct EpsilonGreedy {
+    epsilon: f64,
+}
+
+impl SelectionPolicy for EpsilonGreedy {
+    fn select(
+        &self,
+        rng: &mut SmallRng,
+        values: &mut [(Slot, Key, f64)],
+        k_temp: f64,
+    ) -> (Slot, Key, f64) {
+        if rng.random_bool(self.epsilon) {
+            return UniformPick.select(rng, values, k_temp);
+        }
 
-    let selection = mapped_roulette(rng, values);
+        return *values
+            .iter()
+            .max_by(|a, b| return a.2.partial_cmp(&b.2).unwrap_or(cmp::Ordering::Equal))
+            .expect("Values is empty in EpsilonGreedy");
+    }
+}
 
-    return selection;
+// Ignores the scores entirely. Useful as a baseline for the others
+struct UniformPick;
+
+impl SelectionPolicy for UniformPick {
+    fn select(
+        &self,
+        rng: &mut SmallRng,
+        values: &mut [(Slot, Key, f64)],
+        _k_temp: f64,
+    ) -> (Slot, Key, f64) {
+        return values[rng.random_range(0..values.len())];
+    }
+}
+
+// Roulette on rank rather than score, so one outlier cannot take over the distribution. The worst
+// candidate gets a weight of one, the best a weight of the candidate count
+struct RankPick;
+
+impl SelectionPolicy for RankPick {
+    fn select(
+        &self,
+        rng: &mut SmallRng,
+        values: &mut [(Slot, Key, f64)],
+        _k_temp: f64,
+    ) -> (Slot, Key, f64) {
+        values.sort_by(|a, b| return a.2.partial_cmp(&b.2).unwrap_or(cmp::Ordering::Equal));
+
+        let cnt = values.len() as f64;
+        let total = cnt * (cnt + 1.0_f64) / 2.0_f64;
+        for (rank, v) in values.iter_mut().enumerate() {
+            v.2 = (rank + 1) as f64 / total;
+        }
+
+        return mapped_roulette(rng, values);
+    }
+}
+
+// A uniform pick among the k best candidates
+struct TopK {
+    k: usize,
+}
+
+impl SelectionPolicy for TopK {
+    fn select(
+        &self,
+        rng: &mut SmallRng,
+        values: &mut [(Slot, Key, f64)],
+        _k_temp: f64,
+    ) -> (Slot, Key, f64) {
+        values.sort_by(|a, b| return b.2.partial_cmp(&a.2).unwrap_or(cmp::Ordering::Equal));
+        let k = self.k.clamp(1, values.len());
+
+        return values[rng.random_range(0..k)];
+    }
+}
+
+// Adjusts candidate scores by the weight behind them before select_key. weights lines up with
+// values. Bonuses and noise are scaled by the spread of the candidate scores, so the exploration
+// setting means the same thing however large the score changes are
+pub fn apply_confidence(rng: &mut SmallRng, values: &mut [(Slot, Key, f64)], weights: &[f64]) {
+    debug_assert_eq!(
+        values.len(),
+        weights.len(),
+        "Values and weights have different lengths in apply_confidence"
+    );
+
+    let config = &get_config().swap_table;
+    if config.confidence == Confidence::None || values.is_empty() {
+        return;
+    }
+
+    let cnt = values.len() as f64;
+    let mean = values.iter().fold(0.0_f64, |acc, v| return acc + v.2) / cnt;
+    let var = values
+        .iter()
+        .fold(0.0_f64, |acc, v| return acc + (v.2 - mean).powi(2))
+        / cnt;
+    let spread = if var > 0.0_f64 {
+        var.sqrt()
+    } else {
+        1.0_f64
+    };
+    let scale = spread * config.exploration;
+
+    match config.confidence {
+        Confidence::None => {}
+        Confidence::Ucb => {
+            let total_weight = weights.iter().fold(0.0_f64, |acc, w| return acc + w);
+            let log_total = (total_weight + 1.0_f64).ln();
+            for (v, w) in values.iter_mut().zip(weights) {
+                v.2 += scale * (log_total / (w + 1.0_f64)).sqrt();
+            }
+        }
+        Confidence::Thompson => {
+            for (v, w) in values.iter_mut().zip(weights) {
+                v.2 += scale / (w + 1.0_f64).sqrt() * sample_normal(rng);
+            }
+        }
+    }
 }
 
 pub fn apply_minmax(values: &mut [(Slot, Key, f64)]) {
@@ -152,37 +445,26 @@ pub fn apply_softmax(values: &mut [(Slot, Key, f64)], temp: f64) {
         "Input values contain at least one infinite number in apply_softmax"
     );
 
-    #[cfg(debug_assertions)]
-    {
-        let max_value: f64 = values
-            .iter()
-            .fold(f64::NEG_INFINITY, |acc, v| return acc.max(v.2));
+    // Subtracting the largest exponent keeps exp from overflowing, and leaves at least one term at
+    // 1 so the total cannot underflow to zero
+    let max_scaled: f64 = values
+        .iter()
+        .fold(f64::NEG_INFINITY, |acc, v| return acc.max(v.2 / temp));
 
-        debug_assert!(
-            max_value / temp <= f64::MAX.ln(),
-            "Max value {} / temperature {} > f64::MAX.ln() {} in apply_softmax",
-            max_value,
-            temp,
-            f64::MAX.ln()
-        );
-    }
+    debug_assert!(
+        max_scaled.is_finite(),
+        "Max value / temperature {} is not finite in apply_softmax",
+        max_scaled
+    );
 
     let mut total_scaled = 0.0_f64;
     for c in values.iter_mut() {
-        c.2 = (c.2 / temp).exp();
+        c.2 = (c.2 / temp - max_scaled).exp();
         total_scaled += c.2;
     }
 
-    // Check if total_scaled is zero due to underflow
-    if total_scaled == 0.0_f64 {
-        let uniform_prob = 1.0_f64 / values.len() as f64;
-        for c in values.iter_mut() {
-            c.2 = uniform_prob;
-        }
-    } else {
-        for c in values.iter_mut() {
-            c.2 /= total_scaled;
-        }
+    for c in values.iter_mut() {
+        c.2 /= total_scaled;
     }
 }
 
@@ -1,13 +1,20 @@
-use core::cmp;
+use core::{cmp, iter, mem};
 
 use {
     anyhow::Result,
-    rand::{Rng as _, SeedableRng as _, rngs::SmallRng},
+    rand::{SeedableRng as _, rngs::SmallRng},
 };
 
 use crate::{
-    display::{update_best_kb, update_best_pop_dsp, update_cur_pop_dsp},
+    config::get_config,
+    display::{
+        update_best_kb, update_best_pop_dsp, update_cur_diversity, update_cur_pop_dsp,
+        update_meta_diversity,
+    },
+    diversity::DiversityStats,
+    keyboard::Keyboard,
     population::Population,
+    selection::{SelectionStrategy, take_selected},
     structs::IdSpawner,
 };
 
@@ -29,7 +36,8 @@ impl MetaPopulation {
 
         let mut id_spawner = IdSpawner::new();
 
-        let pop_size = 20;
+        let config = &get_config().meta;
+        let pop_size = config.get_pop_size();
 
         let mut collection = Vec::new();
         for _ in 0..pop_size {
@@ -48,9 +56,9 @@ impl MetaPopulation {
             collection,
             generation,
             top_score,
-            elite_cnt: 1,
+            elite_cnt: config.elite_cnt,
             pop_size,
-            to_remove: 10,
+            to_remove: config.to_remove,
         };
     }
 
@@ -65,6 +73,7 @@ impl MetaPopulation {
             p.eval_gen_pop()?;
             p.filter_climbers();
             p.climb_kbs(self.generation)?;
+            update_cur_diversity(&p.get_diversity())?;
 
             if p.get_top_score() >= self.top_score {
                 self.top_score = p.get_top_score();
@@ -73,9 +82,39 @@ impl MetaPopulation {
             }
         }
 
+        self.migrate();
+        update_meta_diversity(&self.get_diversity())?;
+
         return Ok(());
     }
 
+    // All emigrants are chosen before any arrive, so a keyboard moves at most one hop per
+    // migration
+    fn migrate(&mut self) {
+        let config = &get_config().migration;
+        let Some(topology) = config.topology else {
+            return;
+        };
+
+        if !self.generation.is_multiple_of(config.interval) {
+            return;
+        }
+
+        let pop_cnt = self.collection.len();
+        let mut arrivals: Vec<Vec<Keyboard>> = iter::repeat_with(Vec::new).take(pop_cnt).collect();
+        for source in 0..pop_cnt {
+            let destinations = topology.get_destinations(&mut self.rng, source, pop_cnt);
+            for dest in destinations {
+                let migrants = self.collection[source].get_emigrants(config.count, config.choice);
+                arrivals[dest].extend(migrants);
+            }
+        }
+
+        for (p, migrants) in self.collection.iter_mut().zip(arrivals) {
+            p.receive_migrants(migrants);
+        }
+    }
+
     pub fn purge(&mut self) {
         self.collection.sort_by(|a, b| {
             return b
@@ -92,36 +131,19 @@ impl MetaPopulation {
             c.unset_elite();
         }
 
-        let mut elites: Vec<Population> = Vec::new();
-        for i in 0..self.elite_cnt {
-            elites.push(self.collection.swap_remove(i));
-        }
+        let mut elites: Vec<Population> = self.collection.drain(..self.elite_cnt).collect();
 
-        let mut total_top_score = self
-            .collection
-            .iter()
-            .fold(0.0_f64, |acc, c| return acc + c.get_top_score());
-
-        let mut removed = 0;
-        while removed < self.to_remove {
-            let mut checked_score: f64 = 0.0;
-            let r = self.rng.random_range(0.0_f64..=total_top_score);
-
-            for (i, p) in self.collection.iter_mut().enumerate() {
-                checked_score += p.get_top_score();
-                if checked_score >= r {
-                    total_top_score -= p.get_top_score();
-                    self.collection.swap_remove(i);
-
-                    removed += 1;
-                    break;
-                }
-            }
-        }
+        // NOTE: Survivors are picked, not the populations to remove. Under roulette a high score
+        // makes a population more likely to stay
+        let top_scores = self.get_top_scores();
+        let keep_cnt = self.collection.len().saturating_sub(self.to_remove);
+        let selected = get_config()
+            .selection
+            .meta
+            .select(&mut self.rng, &top_scores, keep_cnt);
+        self.collection = take_selected(mem::take(&mut self.collection), &selected).0;
 
-        for i in 0..elites.len() {
-            self.collection.push(elites.swap_remove(i));
-        }
+        self.collection.append(&mut elites);
     }
 
     pub fn reproduce(&mut self) {
@@ -140,39 +162,10 @@ impl MetaPopulation {
                 self.collection.append(&mut already_reproduced);
             }
 
-            let mut total_top_score = self
-                .collection
-                .iter()
-                .fold(0.0_f64, |acc, c| return acc + c.get_top_score());
-
-            let mut a = self.collection.len();
-            let mut checked_score: f64 = 0.0;
-            let r_a = self.rng.random_range(0.0_f64..=total_top_score);
-
-            for (i, p) in self.collection.iter_mut().enumerate() {
-                checked_score += p.get_top_score();
-                if checked_score >= r_a {
-                    total_top_score -= p.get_top_score();
-
-                    a = i;
-                    break;
-                }
-            }
-
+            let strategy = get_config().selection.meta;
+            let a = self.select_parent(strategy);
             parents.push(self.collection.swap_remove(a));
-            let mut b = self.collection.len();
-
-            checked_score = 0.0_f64;
-            let r_b = self.rng.random_range(0.0_f64..=total_top_score);
-
-            for (i, p) in self.collection.iter_mut().enumerate() {
-                checked_score += p.get_top_score();
-                if checked_score >= r_b {
-                    b = i;
-                    break;
-                }
-            }
-
+            let b = self.select_parent(strategy);
             parents.push(self.collection.swap_remove(b));
             debug_assert_eq!(parents.len(), 2, "In reproduce");
 
@@ -187,4 +180,55 @@ impl MetaPopulation {
         self.collection.append(&mut children);
         debug_assert_eq!(self.collection.len(), self.pop_size, "in reproduce");
     }
+
+    fn get_top_scores(&self) -> Vec<f64> {
+        return self
+            .collection
+            .iter()
+            .map(|p| return p.get_top_score())
+            .collect();
+    }
+
+    fn select_parent(&mut self, strategy: SelectionStrategy) -> usize {
+        let top_scores = self.get_top_scores();
+        let selected = strategy.select(&mut self.rng, &top_scores, 1);
+
+        return selected[0];
+    }
+
+    // Compares the best keyboard of each population
+    pub fn get_diversity(&self) -> DiversityStats {
+        let best_kbs: Vec<&Keyboard> = self
+            .collection
+            .iter()
+            .map(|p| return p.get_best_kb())
+            .collect();
+
+        return DiversityStats::from_keyboards(&best_kbs);
+    }
+
+    pub fn get_best_pop(&self) -> &Population {
+        return self
+            .collection
+            .iter()
+            .max_by(|a, b| {
+                return a
+                    .get_top_score()
+                    .partial_cmp(&b.get_top_score())
+                    .unwrap_or(cmp::Ordering::Equal);
+            })
+            .expect("MetaPopulation collection is empty");
+    }
+
+    pub fn get_best_kb(&self) -> &Keyboard {
+        return self.get_best_pop().get_best_kb();
+    }
+
+    pub fn get_generation(&self) -> usize {
+        return self.generation;
+    }
+
+    pub fn get_top_score(&self) -> f64 {
+        return self.top_score;
+    }
 }
@@ -0,0 +1,77 @@
+use core::{fmt, str::FromStr};
+
+use {
+    anyhow::{Error, anyhow},
+    rand::{Rng as _, rngs::SmallRng},
+};
+
+// Which populations each population sends migrants to. Ring order follows the meta-population's
+// collection, which is reshuffled by purge and reproduce, so neighbours change over time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    Ring,
+    Full,
+    Random,
+}
+
+impl Topology {
+    pub fn get_destinations(self, rng: &mut SmallRng, source: usize, cnt: usize) -> Vec<usize> {
+        if cnt < 2 {
+            return Vec::new();
+        }
+
+        return match self {
+            Topology::Ring => vec![(source + 1) % cnt],
+            Topology::Full => (0..cnt).filter(|i| return *i != source).collect(),
+            Topology::Random => {
+                let mut dest = rng.random_range(0..cnt - 1);
+                if dest >= source {
+                    dest += 1;
+                }
+
+                vec![dest]
+            }
+        };
+    }
+}
+
+impl fmt::Display for Topology {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Topology::Ring => write!(f, "Ring"),
+            Topology::Full => write!(f, "Full"),
+            Topology::Random => write!(f, "Random"),
+        };
+    }
+}
+
+impl FromStr for Topology {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "ring" => Ok(Topology::Ring),
+            "full" => Ok(Topology::Full),
+            "random" => Ok(Topology::Random),
+            _ => Err(anyhow!("expected ring, full, or random")),
+        };
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrantChoice {
+    Best,
+    Random,
+}
+
+impl FromStr for MigrantChoice {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "best" => Ok(MigrantChoice::Best),
+            "random" => Ok(MigrantChoice::Random),
+            _ => Err(anyhow!("expected best or random")),
+        };
+    }
+}
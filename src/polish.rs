@@ -0,0 +1,154 @@
+use core::fmt;
+
+use crate::{
+    keyboard::Keyboard,
+    structs::{Key, Slot},
+};
+
+pub struct Improvement {
+    step: usize,
+    description: String,
+    old_score: f64,
+    new_score: f64,
+}
+
+impl Improvement {
+    pub fn get_gain(&self) -> f64 {
+        return self.new_score - self.old_score;
+    }
+}
+
+impl fmt::Display for Improvement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return write!(
+            f,
+            "Step {:03}: {} -- {:.4} -> {:.4} ({:+.4})",
+            self.step,
+            self.description,
+            self.old_score,
+            self.new_score,
+            self.get_gain()
+        );
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Move {
+    Swap(Slot, Slot),
+    // The key in the first slot moves to the second, the second to the third, and the third to
+    // the first
+    Cycle(Slot, Slot, Slot),
+}
+
+// Steepest descent over the full neighbourhood. Every legal 2-swap (and, if enabled, every legal
+// 3-cycle) is evaluated and the best one applied, until no move improves the score. The result is
+// a local optimum for the moves considered. Each improvement is printed as it is found
+// PERF: Every candidate is a full corpus evaluation. 3-cycles multiply the work by roughly the
+// number of movable keys, so they are off by default
+pub fn polish(keyboard: &Keyboard, use_cycles: bool) -> (Keyboard, Vec<Improvement>) {
+    let mut kb = keyboard.kb_clone();
+    kb.eval();
+
+    let mut improvements: Vec<Improvement> = Vec::new();
+
+    loop {
+        let mut moves: Vec<Move> = kb
+            .get_legal_swaps()
+            .into_iter()
+            .map(|(a, b)| return Move::Swap(a, b))
+            .collect();
+        if use_cycles {
+            moves.extend(get_legal_cycles(&kb));
+        }
+
+        let mut best: Option<(Move, Keyboard)> = None;
+        for candidate_move in moves {
+            let mut candidate = kb.kb_clone();
+            apply_move(&mut candidate, candidate_move);
+            candidate.eval();
+
+            let to_beat = best
+                .as_ref()
+                .map_or(kb.get_score(), |(_, b)| return b.get_score());
+            if candidate.get_score() > to_beat {
+                best = Some((candidate_move, candidate));
+            }
+        }
+
+        let Some((best_move, best_kb)) = best else {
+            break;
+        };
+
+        let improvement = Improvement {
+            step: improvements.len() + 1,
+            description: describe_move(&kb, best_move),
+            old_score: kb.get_score(),
+            new_score: best_kb.get_score(),
+        };
+        println!("{improvement}");
+
+        improvements.push(improvement);
+        kb = best_kb;
+    }
+
+    return (kb, improvements);
+}
+
+// Each rotation of a cycle is the same move, so only cycles starting from the lowest slot are
+// listed. Both directions are kept since they are different moves
+fn get_legal_cycles(kb: &Keyboard) -> Vec<Move> {
+    let movable: Vec<(Slot, Key)> = kb.get_movable_slots();
+    let valid_slots = kb.get_valid_slots();
+    let can_move = |key: Key, slot: Slot| return valid_slots[&key].contains(&slot);
+
+    let mut cycles: Vec<Move> = Vec::new();
+    for (i, (slot_a, key_a)) in movable.iter().enumerate() {
+        for (j, (slot_b, key_b)) in movable.iter().enumerate().skip(i + 1) {
+            for (slot_c, key_c) in movable.iter().skip(j + 1) {
+                if can_move(*key_a, *slot_b)
+                    && can_move(*key_b, *slot_c)
+                    && can_move(*key_c, *slot_a)
+                {
+                    cycles.push(Move::Cycle(*slot_a, *slot_b, *slot_c));
+                }
+
+                if can_move(*key_a, *slot_c)
+                    && can_move(*key_c, *slot_b)
+                    && can_move(*key_b, *slot_a)
+                {
+                    cycles.push(Move::Cycle(*slot_a, *slot_c, *slot_b));
+                }
+            }
+        }
+    }
+
+    return cycles;
+}
+
+fn apply_move(kb: &mut Keyboard, to_apply: Move) {
+    match to_apply {
+        Move::Swap(a, b) => kb.apply_swap(a, b),
+        Move::Cycle(a, b, c) => {
+            // a b c -> b a c -> c a b
+            kb.apply_swap(a, b);
+            kb.apply_swap(a, c);
+        }
+    }
+}
+
+fn describe_move(kb: &Keyboard, to_describe: Move) -> String {
+    let label = |slot: Slot| return kb.get_key_slots()[&slot].get_base();
+
+    return match to_describe {
+        Move::Swap(a, b) => format!("swap {} and {}", label(a), label(b)),
+        Move::Cycle(a, b, c) => format!(
+            "cycle {} to {}'s slot, {} to {}'s slot, {} to {}'s slot",
+            label(a),
+            label(b),
+            label(b),
+            label(c),
+            label(c),
+            label(a)
+        ),
+    };
+}
@@ -3,15 +3,17 @@ use core::cmp;
 use rand::{Rng as _, rngs::SmallRng};
 
 use crate::{
+    config::get_config,
+    diversity::get_selection_fitness,
+    kb_builders::get_swappable_keys,
     keyboard::Keyboard,
     keys,
-    population::{
-        ELITE_CNT, MAX_CLIMB_PCT, MAX_K_TEMP, MAX_MUTATION, MAX_POP, MAX_SCORE_DECAY,
-        MIN_CLIMB_PCT, MIN_K_TEMP, MIN_MUTATION, MIN_POP, MIN_SCORE_DECAY, MUTATION_RATE,
-        Population, SwapScore, SwapTable,
-    },
-    structs::Key,
+    local_search::LocalSearchMethod,
+    mapped_swap::SwapPolicy,
+    population::{Population, SwapScore, SwapTable},
+    selection::{SelectionStrategy, take_selected},
     swappable_keys,
+    variation::{Gene, StepSizes, vary},
 };
 
 swappable_keys!();
@@ -21,14 +23,22 @@ pub fn pop_cnt_from_parents(
     parent_a: &Population,
     parent_b: &Population,
     top_a_pct: f64,
+    step_sizes: &mut StepSizes,
 ) -> usize {
-    return if rng.random_range(0.0..=1.0) <= MUTATION_RATE {
-        rng.random_range(MIN_POP..=MAX_POP)
-    } else if rng.random_range(0.0..=1.0) <= top_a_pct {
-        parent_a.get_pop_cnt()
-    } else {
-        parent_b.get_pop_cnt()
-    };
+    let bounds = &get_config().population;
+    let (pop_cnt, sigma) = vary(
+        rng,
+        Gene::pop_cnt(bounds),
+        (parent_a.get_pop_cnt() as f64, parent_b.get_pop_cnt() as f64),
+        (
+            parent_a.get_step_sizes().pop_cnt,
+            parent_b.get_step_sizes().pop_cnt,
+        ),
+        top_a_pct,
+    );
+    step_sizes.pop_cnt = sigma;
+
+    return (pop_cnt.round() as usize).clamp(bounds.min_pop, bounds.max_pop);
 }
 
 pub fn climb_cnt_from_parents(
@@ -37,18 +47,24 @@ pub fn climb_cnt_from_parents(
     parent_b: &Population,
     top_a_pct: f64,
     pop_cnt: usize,
+    step_sizes: &mut StepSizes,
 ) -> usize {
-    let climb_pct = if rng.random_range(0.0_f64..=1.0_f64) <= MUTATION_RATE {
-        rng.random_range(MIN_CLIMB_PCT..=MAX_CLIMB_PCT)
-    } else if rng.random_range(0.0_f64..=1.0_f64) <= top_a_pct {
-        parent_a.get_climb_pct()
-    } else {
-        parent_b.get_climb_pct()
-    };
+    let bounds = &get_config().population;
+    let (climb_pct, sigma) = vary(
+        rng,
+        Gene::climb_pct(bounds),
+        (parent_a.get_climb_pct(), parent_b.get_climb_pct()),
+        (
+            parent_a.get_step_sizes().climb_pct,
+            parent_b.get_step_sizes().climb_pct,
+        ),
+        top_a_pct,
+    );
+    step_sizes.climb_pct = sigma;
 
     let climber_cnt = (pop_cnt as f64 * climb_pct).round() as usize;
 
-    return climber_cnt.max(ELITE_CNT);
+    return climber_cnt.max(bounds.elite_cnt);
 }
 
 pub fn new_pop_from_parents(
@@ -57,7 +73,9 @@ pub fn new_pop_from_parents(
     parent_b: &Population,
     pop_cnt: usize,
     top_score: f64,
+    selection: SelectionStrategy,
 ) -> Vec<Keyboard> {
+    let bounds = &get_config().population;
     let pop_a: &[Keyboard] = parent_a.get_population();
     let pop_b: &[Keyboard] = parent_b.get_population();
     let mut population: Vec<Keyboard> = Vec::with_capacity(pop_a.len() + pop_b.len());
@@ -79,25 +97,15 @@ pub fn new_pop_from_parents(
         elites.push(population.swap_remove(i));
     }
 
-    let mut full_pop_score = population
-        .iter()
-        .fold(0.0_f64, |acc, p| return acc + p.get_score());
-    debug_assert!(full_pop_score > 0.0_f64, "Parent populations not evaluated");
-
-    while population.len() > (pop_cnt / 4) - elites.len() && !population.is_empty() {
-        let mut checked_score: f64 = 0.0;
-        let r = rng.random_range(0.0_f64..=full_pop_score);
-
-        for (j, kb) in population.iter().enumerate() {
-            checked_score += kb.get_score();
-            if checked_score >= r {
-                full_pop_score -= kb.get_score();
-                population.swap_remove(j);
+    debug_assert!(
+        population.iter().any(|p| return p.get_score() > 0.0_f64),
+        "Parent populations not evaluated"
+    );
 
-                break;
-            }
-        }
-    }
+    let fitness = get_selection_fitness(&population);
+    let keep_cnt = (pop_cnt / 4).saturating_sub(elites.len());
+    let selected = selection.select(rng, &fitness, keep_cnt);
+    population = take_selected(population, &selected).0;
 
     population.append(&mut elites);
     population.sort_by(|a, b| {
@@ -107,11 +115,11 @@ pub fn new_pop_from_parents(
             .unwrap_or(cmp::Ordering::Equal);
     });
 
-    for p in population.iter_mut().take(ELITE_CNT) {
+    for p in population.iter_mut().take(bounds.elite_cnt) {
         p.set_elite();
     }
 
-    for p in population.iter_mut().skip(ELITE_CNT) {
+    for p in population.iter_mut().skip(bounds.elite_cnt) {
         p.unset_elite();
     }
 
@@ -131,13 +139,87 @@ pub fn mutation_from_parents(
     parent_a: &Population,
     parent_b: &Population,
     top_a_pct: f64,
+    step_sizes: &mut StepSizes,
 ) -> usize {
-    return if rng.random_range(0.0..=1.0) <= MUTATION_RATE {
-        rng.random_range(MIN_MUTATION..=MAX_MUTATION)
+    let bounds = &get_config().population;
+    let (mutation, sigma) = vary(
+        rng,
+        Gene::mutation(bounds),
+        (
+            parent_a.get_mutation() as f64,
+            parent_b.get_mutation() as f64,
+        ),
+        (
+            parent_a.get_step_sizes().mutation,
+            parent_b.get_step_sizes().mutation,
+        ),
+        top_a_pct,
+    );
+    step_sizes.mutation = sigma;
+
+    return (mutation.round() as usize).clamp(bounds.min_mutation, bounds.max_mutation);
+}
+
+// If the config fixes a strategy, every population uses it
+pub fn selection_from_parents(
+    rng: &mut SmallRng,
+    parent_a: &Population,
+    parent_b: &Population,
+    top_a_pct: f64,
+) -> SelectionStrategy {
+    let config = get_config();
+    if let Some(selection) = config.selection.population {
+        return selection;
+    }
+
+    return if rng.random_range(0.0..=1.0) <= config.population.mutation_rate {
+        SelectionStrategy::random(rng)
+    } else if rng.random_range(0.0..=1.0) <= top_a_pct {
+        parent_a.get_selection()
+    } else {
+        parent_b.get_selection()
+    };
+}
+
+// If the config fixes a method, every population uses it
+pub fn local_search_from_parents(
+    rng: &mut SmallRng,
+    parent_a: &Population,
+    parent_b: &Population,
+    top_a_pct: f64,
+) -> LocalSearchMethod {
+    let config = get_config();
+    if let Some(local_search) = config.local_search.method {
+        return local_search;
+    }
+
+    return if rng.random_range(0.0..=1.0) <= config.population.mutation_rate {
+        LocalSearchMethod::random(rng)
     } else if rng.random_range(0.0..=1.0) <= top_a_pct {
-        parent_a.get_mutation()
+        parent_a.get_local_search()
     } else {
-        parent_b.get_mutation()
+        parent_b.get_local_search()
+    };
+}
+
+// If the config fixes a policy, every population uses it
+pub fn swap_policy_from_parents(
+    rng: &mut SmallRng,
+    parent_a: &Population,
+    parent_b: &Population,
+    top_a_pct: f64,
+) -> SwapPolicy {
+    let config = get_config();
+    if let Some(swap_policy) = config.swap_policy.method {
+        return swap_policy;
+    }
+
+    return if rng.random_range(0.0..=1.0) <= config.population.mutation_rate {
+        SwapPolicy::random(rng)
+    } else if rng.random_range(0.0..=1.0) <= top_a_pct {
+        parent_a.get_swap_policy()
+    } else {
+        parent_b.get_swap_policy()
     };
 }
 
@@ -147,14 +229,13 @@ pub fn swap_table_from_parents(
     parent_b: &Population,
     top_a_pct: f64,
 ) -> SwapTable {
+    let bounds = &get_config().population;
     let mut swap_table = SwapTable::new();
 
     for j in 0_usize..4_usize {
         for k in 0_usize..10_usize {
-            for key_tuple in &SWAPPABLE_KEYS {
-                let key = Key::from_tuple(*key_tuple);
-
-                if rng.random_range(0.0_f64..=1.0_f64) <= MUTATION_RATE {
+            for key in get_swappable_keys(&SWAPPABLE_KEYS) {
+                if rng.random_range(0.0_f64..=1.0_f64) <= bounds.mutation_rate {
                     swap_table.replace_score(j, k, key, SwapScore::new());
                     continue;
                 }
@@ -167,7 +248,7 @@ pub fn swap_table_from_parents(
                 let weight_a = swap_score_a.get_weights();
                 let weight_b = swap_score_b.get_weights();
 
-                if rng.random_range(0.0_f64..=1.0_f64) >= top_a_pct {
+                if rng.random_range(0.0_f64..=1.0_f64) <= top_a_pct {
                     let new_score = score_a;
                     let new_weight = weight_a;
 
@@ -192,14 +273,21 @@ pub fn k_temp_from_parents(
     parent_a: &Population,
     parent_b: &Population,
     top_a_pct: f64,
+    step_sizes: &mut StepSizes,
 ) -> f64 {
-    return if rng.random_range(0.0..=1.0) <= MUTATION_RATE {
-        rng.random_range(MIN_K_TEMP..=MAX_K_TEMP)
-    } else if rng.random_range(0.0..=1.0) <= top_a_pct {
-        parent_a.get_k_temp()
-    } else {
-        parent_b.get_k_temp()
-    };
+    let (k_temp, sigma) = vary(
+        rng,
+        Gene::k_temp(&get_config().population),
+        (parent_a.get_k_temp(), parent_b.get_k_temp()),
+        (
+            parent_a.get_step_sizes().k_temp,
+            parent_b.get_step_sizes().k_temp,
+        ),
+        top_a_pct,
+    );
+    step_sizes.k_temp = sigma;
+
+    return k_temp;
 }
 
 pub fn score_decay_from_parents(
@@ -207,14 +295,21 @@ pub fn score_decay_from_parents(
     parent_a: &Population,
     parent_b: &Population,
     top_a_pct: f64,
+    step_sizes: &mut StepSizes,
 ) -> f64 {
-    return if rng.random_range(0.0..=1.0) <= MUTATION_RATE {
-        rng.random_range(MIN_SCORE_DECAY..=MAX_SCORE_DECAY)
-    } else if rng.random_range(0.0..=1.0) <= top_a_pct {
-        parent_a.get_score_decay()
-    } else {
-        parent_b.get_score_decay()
-    };
+    let (score_decay, sigma) = vary(
+        rng,
+        Gene::score_decay(&get_config().population),
+        (parent_a.get_score_decay(), parent_b.get_score_decay()),
+        (
+            parent_a.get_step_sizes().score_decay,
+            parent_b.get_step_sizes().score_decay,
+        ),
+        top_a_pct,
+    );
+    step_sizes.score_decay = sigma;
+
+    return score_decay;
 }
 
 pub fn avg_climb_iter_from_parents(parent_a: &Population, parent_b: &Population) -> (f64, usize) {
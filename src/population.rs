@@ -4,42 +4,45 @@ use {alloc::collections::BTreeMap, core::cmp};
 
 use {
     anyhow::Result,
-    rand::{Rng as _, SeedableRng as _, rngs::SmallRng},
+    rand::{Rng as _, SeedableRng as _, rngs::SmallRng, seq::index::sample},
 };
 
 use crate::{
+    config::get_config,
+    crossover::{CrossoverOp, crossover},
     display::{update_climb_info, update_cur_avg, update_eval_dsp},
-    keyboard::Keyboard,
+    diversity::{DiversityStats, get_selection_fitness},
+    kb_builders::get_swappable_keys,
+    keyboard::{Keyboard, is_iso_slot},
     keys,
+    local_search::{LocalSearch as _, LocalSearchMethod, get_new_avg},
+    mapped_swap::SwapPolicy,
+    migration::MigrantChoice,
     pop_helpers::{
         avg_climb_iter_from_parents, climb_cnt_from_parents, k_temp_from_parents,
-        mutation_from_parents, new_pop_from_parents, pop_cnt_from_parents,
-        score_decay_from_parents, swap_table_from_parents,
+        local_search_from_parents, mutation_from_parents, new_pop_from_parents,
+        pop_cnt_from_parents, score_decay_from_parents, selection_from_parents,
+        swap_policy_from_parents, swap_table_from_parents,
     },
+    selection::{SelectionStrategy, take_selected},
     structs::{IdSpawner, Key, Slot},
+    swap_table_file::get_swap_prior,
     swappable_keys,
+    variation::StepSizes,
 };
 
 swappable_keys!();
 
-pub const MIN_POP: usize = 20;
-pub const MAX_POP: usize = 100;
-pub const MIN_CLIMB_PCT: f64 = 0.1;
-pub const MAX_CLIMB_PCT: f64 = 0.4;
-pub const ELITE_CNT: usize = 1;
-
-pub const MIN_MUTATION: usize = 0;
-pub const MAX_MUTATION: usize = 3;
-
-pub const MIN_SCORE_DECAY: f64 = 0.9;
-pub const MAX_SCORE_DECAY: f64 = 0.998;
-
-pub const MIN_K_TEMP: f64 = -31.162_892_36;
-pub const MAX_K_TEMP: f64 = -6.107_632_992;
-
-pub const MUTATION_RATE: f64 = 0.05;
+// The hyperparameters a new population is created with
+#[derive(Debug, Clone, Copy)]
+pub struct PopParams {
+    pub pop_cnt: usize,
+    pub climb_pct: f64,
+    pub mutation: usize,
+    pub k_temp: f64,
+    pub score_decay: f64,
+}
 
-// FUTURE: Consider letting populations engage in tournament selection
 // FUTURE: Generation should be meta-population controlled
 pub struct Population {
     id: usize,
@@ -50,9 +53,13 @@ pub struct Population {
     climber_cnt: usize,
     elite_cnt: usize,
     mutation: usize,
+    selection: SelectionStrategy,
+    local_search: LocalSearchMethod,
+    swap_policy: SwapPolicy,
     swap_table: SwapTable,
     k_temp: f64,
     score_decay: f64,
+    step_sizes: StepSizes,
     generation: usize,
     top_score: f64,
     total_climbs: usize,
@@ -67,18 +74,30 @@ impl Population {
     // FUTURE: Sloppy, but don't want to get into deep refactor without knowing how the
     // meta-population management will be handled
     // FUTURE: Could do bigger populations and/or more climbers after multi-threading
-    // FUTURE: Add an option to cull some bottom % of the population
-    // FUTURE: Add an option to do tournament mode for thinning population
     pub fn create(id_in: usize) -> Self {
         let seed: [u8; 32] = rand::random();
         let mut rng = SmallRng::from_seed(seed);
 
+        let bounds = &get_config().population;
+        let params = PopParams {
+            pop_cnt: rng.random_range(bounds.min_pop..=bounds.max_pop),
+            climb_pct: rng.random_range(bounds.min_climb_pct..=bounds.max_climb_pct),
+            mutation: rng.random_range(bounds.min_mutation..=bounds.max_mutation),
+            k_temp: rng.random_range(bounds.min_k_temp..=bounds.max_k_temp),
+            score_decay: rng.random_range(bounds.min_score_decay..=bounds.max_score_decay),
+        };
+
+        return Self::with_params(id_in, rng, params);
+    }
+
+    // Selection and local search still come from the config, or are drawn from rng if the config
+    // leaves them to evolve
+    pub fn with_params(id_in: usize, mut rng: SmallRng, params: PopParams) -> Self {
         let mut id_spawner = IdSpawner::new();
 
-        let pop_cnt = rng.random_range(MIN_POP..=MAX_POP);
-        let climb_pct = rng.random_range(MIN_CLIMB_PCT..=MAX_CLIMB_PCT);
-        let climber_cnt = (pop_cnt as f64 * climb_pct).round() as usize;
-        let elite_cnt = ELITE_CNT;
+        let pop_cnt = params.pop_cnt;
+        let climber_cnt = (pop_cnt as f64 * params.climb_pct).round() as usize;
+        let elite_cnt = get_config().population.elite_cnt;
 
         assert!(
             elite_cnt <= climber_cnt,
@@ -88,7 +107,18 @@ impl Population {
         let population: Vec<Keyboard> = Vec::with_capacity(pop_cnt);
         let mut climbers: Vec<Keyboard> = Vec::with_capacity(climber_cnt);
 
-        let mutation = rng.random_range(MIN_MUTATION..=MAX_MUTATION);
+        let selection = get_config()
+            .selection
+            .population
+            .unwrap_or_else(|| return SelectionStrategy::random(&mut rng));
+        let local_search = get_config()
+            .local_search
+            .method
+            .unwrap_or_else(|| return LocalSearchMethod::random(&mut rng));
+        let swap_policy = get_config()
+            .swap_policy
+            .method
+            .unwrap_or_else(|| return SwapPolicy::random(&mut rng));
 
         // New population members are created at the beginning of each iteration, so fill the
         // climbers now
@@ -98,10 +128,6 @@ impl Population {
             climbers.push(keyboard);
         }
 
-        let k_temp = rng.random_range(MIN_K_TEMP..=MAX_K_TEMP);
-
-        let score_decay = rng.random_range(MIN_SCORE_DECAY..=MAX_SCORE_DECAY);
-
         return Self {
             id: id_in,
             rng,
@@ -110,10 +136,14 @@ impl Population {
             population,
             climber_cnt,
             elite_cnt,
-            mutation,
-            swap_table: SwapTable::new(),
-            k_temp,
-            score_decay,
+            mutation: params.mutation,
+            selection,
+            local_search,
+            swap_policy,
+            swap_table: get_swap_prior().map_or_else(SwapTable::new, Clone::clone),
+            k_temp: params.k_temp,
+            score_decay: params.score_decay,
+            step_sizes: StepSizes::new(),
             generation: 0,
             top_score: 0.0,
             total_climbs: 0,
@@ -136,15 +166,30 @@ impl Population {
         let total_top = top_a + top_b;
         let top_a_pct = top_a / total_top;
 
-        let pop_cnt = pop_cnt_from_parents(&mut rng, parent_a, parent_b, top_a_pct);
-        let climber_cnt = climb_cnt_from_parents(&mut rng, parent_a, parent_b, top_a_pct, pop_cnt);
-        let mutation = mutation_from_parents(&mut rng, parent_a, parent_b, top_a_pct);
-
-        let population = new_pop_from_parents(&mut rng, parent_a, parent_b, pop_cnt, top_score);
+        let mut step_sizes = StepSizes::new();
+        let pop_cnt =
+            pop_cnt_from_parents(&mut rng, parent_a, parent_b, top_a_pct, &mut step_sizes);
+        let climber_cnt = climb_cnt_from_parents(
+            &mut rng,
+            parent_a,
+            parent_b,
+            top_a_pct,
+            pop_cnt,
+            &mut step_sizes,
+        );
+        let mutation =
+            mutation_from_parents(&mut rng, parent_a, parent_b, top_a_pct, &mut step_sizes);
+        let selection = selection_from_parents(&mut rng, parent_a, parent_b, top_a_pct);
+        let local_search = local_search_from_parents(&mut rng, parent_a, parent_b, top_a_pct);
+        let swap_policy = swap_policy_from_parents(&mut rng, parent_a, parent_b, top_a_pct);
+
+        let population =
+            new_pop_from_parents(&mut rng, parent_a, parent_b, pop_cnt, top_score, selection);
         let swap_table = swap_table_from_parents(&mut rng, parent_a, parent_b, top_a_pct);
 
-        let k_temp = k_temp_from_parents(&mut rng, parent_a, parent_b, top_a_pct);
-        let score_decay = score_decay_from_parents(&mut rng, parent_a, parent_b, top_a_pct);
+        let k_temp = k_temp_from_parents(&mut rng, parent_a, parent_b, top_a_pct, &mut step_sizes);
+        let score_decay =
+            score_decay_from_parents(&mut rng, parent_a, parent_b, top_a_pct, &mut step_sizes);
 
         let generation = parent_a.get_generation().max(parent_b.get_generation());
 
@@ -157,11 +202,15 @@ impl Population {
             pop_cnt,
             population,
             climber_cnt,
-            elite_cnt: ELITE_CNT,
+            elite_cnt: get_config().population.elite_cnt,
             mutation,
+            selection,
+            local_search,
+            swap_policy,
             swap_table,
             k_temp,
             score_decay,
+            step_sizes,
             generation,
             top_score,
             total_climbs,
@@ -182,13 +231,18 @@ impl Population {
         );
 
         let to_add = self.pop_cnt - self.population.len();
+        let parent_cnt = self.population.len();
         for _ in 0..to_add {
-            let new_kb = Keyboard::from_swap_table(
-                &self.swap_table,
-                self.generation,
-                self.id_spawner.get(),
-                self.k_temp,
-            );
+            let new_kb = match self.breed(parent_cnt) {
+                Some(child) => child,
+                None => Keyboard::from_swap_table(
+                    &self.swap_table,
+                    self.generation,
+                    self.id_spawner.get(),
+                    self.k_temp,
+                    self.swap_policy,
+                ),
+            };
             self.population.push(new_kb);
         }
 
@@ -205,6 +259,31 @@ impl Population {
         );
     }
 
+    // Parents are drawn uniformly from the surviving climbers, which are already the product of
+    // selection. New keyboards pushed this generation are not eligible
+    fn breed(&mut self, parent_cnt: usize) -> Option<Keyboard> {
+        let config = &get_config().crossover;
+        let op = config.op?;
+        if parent_cnt < 2 || !self.rng.random_bool(config.rate) {
+            return None;
+        }
+
+        let a = self.rng.random_range(0..parent_cnt);
+        let mut b = self.rng.random_range(0..parent_cnt - 1);
+        if b >= a {
+            b += 1;
+        }
+
+        let key_slots = crossover(&mut self.rng, op, &self.population[a], &self.population[b])?;
+
+        return Some(Keyboard::from_crossover(
+            key_slots,
+            self.generation,
+            self.id_spawner.get(),
+            op,
+        ));
+    }
+
     pub fn eval_gen_pop(&mut self) -> Result<()> {
         for (i, kb) in self.population.iter_mut().enumerate() {
             let display_num = i.checked_add(1).expect("Population has too many to count");
@@ -234,7 +313,7 @@ impl Population {
             }
         }
 
-        assert_eq!(
+        debug_assert_eq!(
             self.population[0].get_score(),
             self.top_score,
             "Elite lost in eval_gen_pop"
@@ -257,25 +336,14 @@ impl Population {
             climbers.push(self.population.swap_remove(i));
         }
 
-        let mut population_score = self
-            .population
-            .iter()
-            .fold(0.0_f64, |acc, p| return acc + p.get_score());
-
-        while climbers.len() < self.climber_cnt && !self.population.is_empty() {
-            let mut checked_score: f64 = 0.0;
-            let r = self.rng.random_range(0.0_f64..=population_score);
-
-            for (j, kb) in self.population.iter_mut().enumerate() {
-                checked_score += kb.get_score();
-                if checked_score >= r {
-                    population_score -= kb.get_score();
-                    climbers.push(self.population.swap_remove(j));
+        self.cull_bottom(self.climber_cnt.saturating_sub(climbers.len()));
 
-                    break;
-                }
-            }
-        }
+        let fitness = get_selection_fitness(&self.population);
+        let to_select = self.climber_cnt.saturating_sub(climbers.len());
+        let selected = self.selection.select(&mut self.rng, &fitness, to_select);
+        let (mut chosen, rest) = take_selected(std::mem::take(&mut self.population), &selected);
+        climbers.append(&mut chosen);
+        self.population = rest;
 
         debug_assert!(
             !climbers.is_empty(),
@@ -297,21 +365,46 @@ impl Population {
         self.population.append(&mut climbers);
     }
 
+    // Drops the lowest scoring share of the non-elite population before climbers are picked, but
+    // never below the number still needed
+    fn cull_bottom(&mut self, needed: usize) {
+        let cull_pct = get_config().selection.cull_pct;
+        if cull_pct <= 0.0 {
+            return;
+        }
+
+        self.population.sort_by(|a, b| {
+            return b
+                .get_score()
+                .partial_cmp(&a.get_score())
+                .unwrap_or(cmp::Ordering::Equal);
+        });
+
+        let cull_cnt = (self.population.len() as f64 * cull_pct).floor() as usize;
+        let keep = (self.population.len() - cull_cnt).max(needed);
+        self.population.truncate(keep);
+    }
+
     pub fn climb_kbs(&mut self, iter: usize) -> Result<()> {
         let mut climber_score = 0.0_f64;
         self.update_climb_decay(iter);
 
         for i in 0..self.population.len() {
             let climb_info = format!(
-                "Keyboard: {:02}, Generation: {:05}, ID: {:07}",
+                "Keyboard: {:02}, Generation: {:05}, ID: {:07}, Crossover: {:<11}",
                 i.checked_add(1).expect("Too many climbers in climb_kbs"),
                 self.population[i].get_generation(),
-                self.population[i].get_id()
+                self.population[i].get_id(),
+                self.population[i]
+                    .get_crossover()
+                    .map_or("None", CrossoverOp::get_name)
             );
             update_climb_info(&climb_info)?;
 
             // Because climb_kbs borrows self as &mut, we can't double-borrow. Clone instead
-            self.population[i] = self.climb_kb(self.population[i].kb_clone());
+            let method = self.local_search;
+            let kb = self.population[i].kb_clone();
+            self.population[i] = method.climb(self, kb);
             climber_score += self.population[i].get_score();
         }
 
@@ -337,7 +430,7 @@ impl Population {
             p.unset_elite();
         }
 
-        assert_eq!(
+        debug_assert_eq!(
             self.population[0].get_score(),
             self.top_score,
             "Elite lost in climb_kbs"
@@ -346,56 +439,93 @@ impl Population {
         return Ok(());
     }
 
-    // NOTE: Changing one key at a time works best. If you change two keys, the algorithm will find
-    // bigger changes less frequently. This causes the decay to continue for about as many
-    // iterations as it would if doing only one step, but fewer improvements will be found, causing
-    // the improvement at the end of the hill climbing step to be lower
-    fn climb_kb(&mut self, keyboard: Keyboard) -> Keyboard {
-        let mut last_improvement: f64 = 0.0;
-        let mut avg_improvement: f64 = 0.0;
-        let mut weighted_avg: f64 = 0.0;
-        let mut sum_weights: f64 = 0.0;
+    // Copies of keyboards to send to another population. Copies are never elite in their new home
+    // until they win a place there. Expects the population to be sorted, as it is after climbing
+    pub fn get_emigrants(&mut self, cnt: usize, choice: MigrantChoice) -> Vec<Keyboard> {
+        let picks: Vec<usize> = match choice {
+            MigrantChoice::Best => (0..cnt.min(self.population.len())).collect(),
+            MigrantChoice::Random => sample(
+                &mut self.rng,
+                self.population.len(),
+                cnt.min(self.population.len()),
+            )
+            .into_vec(),
+        };
 
-        let mut kb = keyboard;
+        return picks
+            .into_iter()
+            .map(|i| {
+                let mut migrant = self.population[i].kb_clone();
+                migrant.unset_elite();
+                return migrant;
+            })
+            .collect();
+    }
+
+    // Migrants replace the lowest scoring non-elite keyboards. If more arrive than there are
+    // non-elites, the lowest scoring migrants are dropped. Elite status and the top score are then
+    // rechecked, since a migrant can beat the current elite
+    pub fn receive_migrants(&mut self, mut migrants: Vec<Keyboard>) {
+        let open_cnt = self.population.len().saturating_sub(self.elite_cnt);
+        migrants.sort_by(|a, b| {
+            return b
+                .get_score()
+                .partial_cmp(&a.get_score())
+                .unwrap_or(cmp::Ordering::Equal);
+        });
+        migrants.truncate(open_cnt);
 
-        for i in 1..=100_000 {
-            let mut climb_kb = kb.kb_clone();
-            if climb_kb.table_swap(&self.swap_table, self.k_temp) {
-                climb_kb.eval();
-                self.update_from_swap(climb_kb.get_last_swap_info());
-            }
+        self.population
+            .truncate(self.population.len() - migrants.len());
+        self.population.append(&mut migrants);
 
-            let this_improvement = (climb_kb.get_score() - kb.get_score()).max(0.0);
-            avg_improvement = get_new_avg(this_improvement, avg_improvement, i);
+        self.population.sort_by(|a, b| {
+            return b
+                .get_score()
+                .partial_cmp(&a.get_score())
+                .unwrap_or(cmp::Ordering::Equal);
+        });
 
-            let improvement_delta = this_improvement - last_improvement;
-            last_improvement = this_improvement;
+        if self.population[0].get_score() >= self.top_score {
+            self.top_score = self.population[0].get_score();
+        }
 
-            let this_weight = get_weight(improvement_delta);
-            sum_weights *= self.climb_decay;
-            let inflated_w_avg = weighted_avg * sum_weights;
-            sum_weights += this_weight;
-            weighted_avg = (inflated_w_avg + this_improvement * this_weight) / sum_weights;
+        for p in self.population.iter_mut().take(self.elite_cnt) {
+            p.set_elite();
+        }
 
-            if climb_kb.get_score() > kb.get_score() {
-                climb_kb.add_pos_iter();
-                kb = climb_kb;
-            }
+        for p in self.population.iter_mut().skip(self.elite_cnt) {
+            p.unset_elite();
+        }
 
-            // Check i > 1 to paste over an edge case where the first improvement on the first
-            // iteration is smaller than the unweighted mean due to floating point imprecision
-            let plateauing: bool = weighted_avg < avg_improvement && i > 1;
-            let i_f64 = i as f64;
-            let not_starting: bool = avg_improvement <= 0.0 && i_f64 >= self.avg_climb_iter;
-            if plateauing || not_starting {
-                self.total_climbs += 1;
-                self.avg_climb_iter = get_new_avg(i_f64, self.avg_climb_iter, self.total_climbs);
+        debug_assert_eq!(
+            self.population[0].get_score(),
+            self.top_score,
+            "Elite lost in receive_migrants"
+        );
+    }
 
-                break;
-            }
+    // Tries one swap from the swap table on a copy of kb. The result is evaluated and fed back into
+    // the swap table. Returns None if no legal swap was found
+    pub fn table_step(&mut self, kb: &Keyboard) -> Option<Keyboard> {
+        let mut climb_kb = kb.kb_clone();
+        if !climb_kb.table_swap(&self.swap_table, self.k_temp, self.swap_policy) {
+            return None;
         }
 
-        return kb;
+        climb_kb.eval();
+        self.record_swap(&climb_kb);
+
+        return Some(climb_kb);
+    }
+
+    pub fn record_swap(&mut self, kb: &Keyboard) {
+        self.update_from_swap(kb.get_last_swap_info());
+    }
+
+    pub fn record_climb(&mut self, iters: usize) {
+        self.total_climbs += 1;
+        self.avg_climb_iter = get_new_avg(iters as f64, self.avg_climb_iter, self.total_climbs);
     }
 
     fn update_climb_decay(&mut self, iter: usize) {
@@ -411,6 +541,15 @@ impl Population {
         let last_key_b = swap_info.3;
         let score_diff = swap_info.4;
 
+        // Layer keys only trade places with each other, and the swap table does not cover layers,
+        // the ISO key, or blanks
+        let off_table = |slot: Slot, key: Key| {
+            return slot.get_layer() > 0 || is_iso_slot(slot) || key == Key::BLANK;
+        };
+        if off_table(last_slot_a, last_key_a) || off_table(last_slot_b, last_key_b) {
+            return;
+        }
+
         self.swap_table
             .update_score(last_slot_a, last_key_a, score_diff, self.score_decay);
         self.swap_table
@@ -421,6 +560,11 @@ impl Population {
         return self.top_score;
     }
 
+    pub fn get_diversity(&self) -> DiversityStats {
+        let kbs: Vec<&Keyboard> = self.population.iter().collect();
+        return DiversityStats::from_keyboards(&kbs);
+    }
+
     pub fn get_best_kb(&self) -> &Keyboard {
         if self.population[0].get_score() == self.get_top_score() {
             return &self.population[0];
@@ -474,10 +618,38 @@ impl Population {
         return self.mutation;
     }
 
+    pub fn get_selection(&self) -> SelectionStrategy {
+        return self.selection;
+    }
+
+    pub fn get_local_search(&self) -> LocalSearchMethod {
+        return self.local_search;
+    }
+
+    pub fn get_swap_policy(&self) -> SwapPolicy {
+        return self.swap_policy;
+    }
+
+    pub fn get_climb_decay(&self) -> f64 {
+        return self.climb_decay;
+    }
+
+    pub fn get_rng(&mut self) -> &mut SmallRng {
+        return &mut self.rng;
+    }
+
     pub fn get_score_decay(&self) -> f64 {
         return self.score_decay;
     }
 
+    pub fn get_swap_table(&self) -> &SwapTable {
+        return &self.swap_table;
+    }
+
+    pub fn get_step_sizes(&self) -> &StepSizes {
+        return &self.step_sizes;
+    }
+
     pub fn get_k_temp(&self) -> f64 {
         return self.k_temp;
     }
@@ -503,30 +675,7 @@ impl Population {
     // }
 }
 
-fn get_new_avg(new_value: f64, old_avg: f64, new_count: usize) -> f64 {
-    let new_value_for_new_avg: f64 = new_value / (new_count as f64);
-    let old_avg_for_new_avg: f64 = old_avg * ((new_count as f64 - 1.0) / new_count as f64);
-
-    return new_value_for_new_avg + old_avg_for_new_avg;
-}
-
-// The strong weight toward positive iterations is to give hill climbers the chance to catch up in
-// later generations
-fn get_weight(delta: f64) -> f64 {
-    const K: f64 = 0.01;
-
-    if delta <= 0.0_f64 {
-        return 1.0;
-    }
-
-    return 1.0 + K * delta.powf(0.9);
-
-    // Alternatives:
-    // return 1.0 + K * delta.sqrt();
-    // return 1.0 + K * delta.ln();
-    // return 1.0 + K * delta.powf(0.0001);
-}
-
+#[derive(Clone)]
 pub struct SwapTable {
     swap_table: Vec<Vec<BTreeMap<Key, SwapScore>>>,
 }
@@ -537,13 +686,14 @@ impl SwapTable {
     // the slot in get_score, but that feels like a hack
     pub fn new() -> Self {
         let mut swap_table: Vec<Vec<BTreeMap<Key, SwapScore>>> = Vec::new();
+        let swappable_keys = get_swappable_keys(&SWAPPABLE_KEYS);
 
         for _ in 0_usize..4_usize {
             let mut row: Vec<BTreeMap<Key, SwapScore>> = Vec::new();
             for _ in 0_usize..10_usize {
                 let mut swap_options: BTreeMap<Key, SwapScore> = BTreeMap::new();
-                for key in &SWAPPABLE_KEYS {
-                    swap_options.insert(Key::from_tuple(*key), SwapScore::new());
+                for key in &swappable_keys {
+                    swap_options.insert(*key, SwapScore::new());
                 }
 
                 row.push(swap_options);
@@ -572,6 +722,15 @@ impl SwapTable {
         return self.swap_table[row][col][key].get_w_avg();
     }
 
+    // How much evidence is behind get_score. Decays along with the average
+    #[expect(clippy::trivially_copy_pass_by_ref)]
+    pub fn get_weight(&self, slot: &Slot, key: &Key) -> f64 {
+        let row = slot.get_row();
+        let col = slot.get_col();
+
+        return self.swap_table[row][col][key].get_weights();
+    }
+
     fn get_swap_score(&self, row: usize, col: usize, key: Key) -> SwapScore {
         return self.swap_table[row][col][&key];
     }
@@ -588,6 +747,33 @@ impl SwapTable {
     pub fn replace_score(&mut self, row: usize, col: usize, key: Key, new_score: SwapScore) {
         self.swap_table[row][col].insert(key, new_score);
     }
+
+    // Every slot and key in the table, in row then column order
+    pub fn get_entries(&self) -> Vec<(Slot, Key, SwapScore)> {
+        let mut entries: Vec<(Slot, Key, SwapScore)> = Vec::new();
+        for (row, cols) in self.swap_table.iter().enumerate() {
+            for (col, scores) in cols.iter().enumerate() {
+                let slot = Slot::from_tuple((row, col));
+                entries.extend(
+                    scores
+                        .iter()
+                        .map(|(key, score)| return (slot, *key, *score)),
+                );
+            }
+        }
+
+        return entries;
+    }
+
+    // Scales how much past observations count without changing the averages. At zero, the first
+    // new observation for an entry replaces its average outright
+    pub fn decay_weights(&mut self, factor: f64) {
+        for scores in self.swap_table.iter_mut().flatten() {
+            for score in scores.values_mut() {
+                score.weights *= factor;
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
@@ -616,7 +802,7 @@ impl SwapScore {
     }
 
     pub fn get_weights(&self) -> f64 {
-        return self.w_avg;
+        return self.weights;
     }
 
     pub fn reweight_avg(&mut self, new_score: f64, decay: f64) {
@@ -0,0 +1,429 @@
+extern crate alloc;
+
+use {
+    alloc::collections::BTreeMap,
+    core::fmt::Write as _,
+    std::{fs, path::Path},
+};
+
+use anyhow::{Result, anyhow};
+
+use crate::{
+    config::get_config,
+    corpus::get_corpus_groups,
+    eval_funcs::{is_same_finger, is_scissor},
+    keyboard::{Finger, Hand, Keyboard, get_col_pos, get_thumb_keys},
+    layout_file::key_label,
+    structs::Slot,
+};
+
+// Key size in pixels. One unit is the width of a standard 1u key
+const UNIT: f64 = 54.0;
+const KEY_GAP: f64 = 4.0;
+const MARGIN: f64 = 20.0;
+
+// Horizontal offset of each row, in units, matching the stagger of an ANSI board. The thumb row
+// is drawn below the bottom row, under the inner columns, and the ISO key left of the bottom row
+const ROW_OFFSETS: [f64; 5] = [1.0, 1.5, 1.75, 2.25, 2.25];
+
+const LIST_LEN: usize = 10;
+
+const FINGER_ORDER: [(Hand, Finger, &str); 10] = [
+    (Hand::Left, Finger::Pinky, "LP"),
+    (Hand::Left, Finger::Ring, "LR"),
+    (Hand::Left, Finger::Middle, "LM"),
+    (Hand::Left, Finger::Index, "LI"),
+    (Hand::Left, Finger::Thumb, "LT"),
+    (Hand::Right, Finger::Thumb, "RT"),
+    (Hand::Right, Finger::Index, "RI"),
+    (Hand::Right, Finger::Middle, "RM"),
+    (Hand::Right, Finger::Ring, "RR"),
+    (Hand::Right, Finger::Pinky, "RP"),
+];
+
+pub struct UsageStats {
+    key_cnts: BTreeMap<Slot, usize>,
+    finger_cnts: BTreeMap<(Hand, Finger), usize>,
+    total: usize,
+    left: usize,
+    right: usize,
+    same_fingers: BTreeMap<(Slot, Slot), usize>,
+    scissors: BTreeMap<(Slot, Slot), usize>,
+}
+
+impl UsageStats {
+    // History is broken on unmapped characters and between corpora in the same way as
+    // Keyboard::eval
+    pub fn from_keyboard(kb: &Keyboard) -> Self {
+        let mut stats = Self {
+            key_cnts: BTreeMap::new(),
+            finger_cnts: BTreeMap::new(),
+            total: 0,
+            left: 0,
+            right: 0,
+            same_fingers: BTreeMap::new(),
+            scissors: BTreeMap::new(),
+        };
+
+        for entries in get_corpus_groups() {
+            let mut last_slot: Option<Slot> = None;
+            for entry in entries {
+                for c in entry.chars() {
+                    let Some(this_slot) = kb.get_char_slot(c) else {
+                        last_slot = None;
+                        continue;
+                    };
+
+                    // Counts are kept per key, including layer keys. Fingers are read from where
+                    // the key sits under the hand
+                    let phys_slot = this_slot.get_phys();
+                    *stats.key_cnts.entry(this_slot).or_insert(0) += 1;
+                    let hand = Hand::from_slot(phys_slot);
+                    let finger = Finger::from_slot(phys_slot);
+                    *stats.finger_cnts.entry((hand, finger)).or_insert(0) += 1;
+                    stats.total += 1;
+                    if finger != Finger::Thumb {
+                        if hand == Hand::Left {
+                            stats.left += 1;
+                        } else {
+                            stats.right += 1;
+                        }
+                    }
+
+                    if let Some(last) = last_slot {
+                        let phys_last = last.get_phys();
+                        if is_same_finger(phys_slot, phys_last) {
+                            *stats.same_fingers.entry((last, this_slot)).or_insert(0) += 1;
+                        } else if is_scissor(phys_slot, phys_last) {
+                            *stats.scissors.entry((last, this_slot)).or_insert(0) += 1;
+                        }
+                    }
+
+                    last_slot = Some(this_slot);
+                }
+            }
+        }
+
+        return stats;
+    }
+
+    fn get_pct(&self, cnt: usize) -> f64 {
+        if self.total == 0 {
+            return 0.0;
+        }
+
+        return cnt as f64 / self.total as f64 * 100.0;
+    }
+}
+
+/// Writes an SVG or a self-contained HTML report, depending on the extension of `path`.
+pub fn write_report(kb: &Keyboard, title: &str, path: &Path) -> Result<()> {
+    let stats = UsageStats::from_keyboard(kb);
+    let svg = render_svg(kb, &stats, title);
+
+    let extension = path
+        .extension()
+        .and_then(|e| return e.to_str())
+        .map(str::to_lowercase);
+    let contents = match extension.as_deref() {
+        Some("svg") => svg,
+        Some("html" | "htm") => render_html(&svg, title),
+        _ => {
+            return Err(anyhow!(
+                "Report path {} must end in .svg or .html",
+                path.display()
+            ));
+        }
+    };
+
+    fs::write(path, contents)?;
+
+    return Ok(());
+}
+
+fn render_html(svg: &str, title: &str) -> String {
+    let mut html = String::new();
+    let _ = writeln!(html, "<!DOCTYPE html>");
+    let _ = writeln!(html, "<html>");
+    let _ = writeln!(html, "<head>");
+    let _ = writeln!(html, "<meta charset=\"utf-8\">");
+    let _ = writeln!(html, "<title>{}</title>", escape_xml(title));
+    let _ = writeln!(
+        html,
+        "<style>body {{ font-family: sans-serif; background: #fafafa; }}</style>"
+    );
+    let _ = writeln!(html, "</head>");
+    let _ = writeln!(html, "<body>");
+    let _ = writeln!(html, "{svg}");
+    let _ = writeln!(html, "</body>");
+    let _ = writeln!(html, "</html>");
+
+    return html;
+}
+
+fn render_svg(kb: &Keyboard, stats: &UsageStats, title: &str) -> String {
+    const BAR_HEIGHT: f64 = 120.0;
+    const BAR_WIDTH: f64 = 40.0;
+    const LINE_HEIGHT: f64 = 18.0;
+
+    // The home row of an ISO board is the widest row
+    let kb_width = (ROW_OFFSETS[2] + 13.0) * UNIT;
+    let kb_top = MARGIN + 30.0;
+    // Thumb and layer rows are drawn below the main rows
+    let row_cnt = kb
+        .get_key_slots()
+        .keys()
+        .chain(get_thumb_keys().keys())
+        .map(|s| return s.get_row() + 1)
+        .max()
+        .unwrap_or(0) as f64;
+    let bars_top = kb_top + row_cnt * UNIT + 40.0;
+    let balance_top = bars_top + BAR_HEIGHT + 50.0;
+    let lists_top = balance_top + 50.0;
+    let height = lists_top + LINE_HEIGHT * (LIST_LEN as f64 + 2.0) + MARGIN;
+    let width = kb_width + MARGIN * 2.0;
+
+    let mut svg = String::new();
+    let _ = writeln!(
+        svg,
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         font-family=\"sans-serif\" font-size=\"12\">"
+    );
+    let _ = writeln!(
+        svg,
+        "<rect width=\"{width}\" height=\"{height}\" fill=\"#ffffff\"/>"
+    );
+    let _ = writeln!(
+        svg,
+        "<text x=\"{MARGIN}\" y=\"{}\" font-size=\"18\">{} (score {:.2})</text>",
+        MARGIN + 10.0,
+        escape_xml(title),
+        kb.get_score()
+    );
+
+    // Keys
+    let max_cnt = stats.key_cnts.values().copied().max().unwrap_or(0);
+    for (slot, key) in kb.get_key_slots().iter().chain(get_thumb_keys()) {
+        let cnt = stats.key_cnts.get(slot).copied().unwrap_or(0);
+        let heat = if max_cnt > 0 {
+            cnt as f64 / max_cnt as f64
+        } else {
+            0.0
+        };
+
+        let x = MARGIN + (ROW_OFFSETS[slot.get_phys().get_row()] + get_col_pos(*slot)) * UNIT;
+        let y = kb_top + slot.get_row() as f64 * UNIT;
+        let size = UNIT - KEY_GAP;
+        // Each finger's home key is outlined, so reassigned fingers and mods show where the hands
+        // rest
+        let phys_slot = slot.get_phys();
+        let stroke = if Finger::get_home_slot(phys_slot) == phys_slot {
+            "#333333"
+        } else {
+            "#999999"
+        };
+
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{x}\" y=\"{y}\" width=\"{size}\" height=\"{size}\" rx=\"4\" \
+             fill=\"{}\" stroke=\"{stroke}\"/>",
+            heat_color(heat)
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"16\">{}</text>",
+            x + size / 2.0,
+            y + size / 2.0,
+            escape_xml(&key_label(key.get_base()))
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"10\">{:.2}%</text>",
+            x + size / 2.0,
+            y + size - 6.0,
+            stats.get_pct(cnt)
+        );
+    }
+
+    // Finger load
+    let _ = writeln!(
+        svg,
+        "<text x=\"{MARGIN}\" y=\"{}\" font-size=\"14\">Finger load</text>",
+        bars_top - 10.0
+    );
+    let max_finger = stats.finger_cnts.values().copied().max().unwrap_or(0);
+    let finger_total: usize = stats.finger_cnts.values().sum();
+    let load_targets = get_config().fingers.load_targets.as_ref();
+    let target_total =
+        load_targets.map_or(0.0, |t| return t.iter().fold(0.0, |acc, x| return acc + x));
+    for (i, (hand, finger, label)) in FINGER_ORDER.iter().enumerate() {
+        let cnt = stats
+            .finger_cnts
+            .get(&(*hand, *finger))
+            .copied()
+            .unwrap_or(0);
+        let bar_height = if max_finger > 0 {
+            cnt as f64 / max_finger as f64 * BAR_HEIGHT
+        } else {
+            0.0
+        };
+
+        let x = MARGIN + i as f64 * (BAR_WIDTH + 12.0);
+        let y = bars_top + BAR_HEIGHT - bar_height;
+        let _ = writeln!(
+            svg,
+            "<rect x=\"{x}\" y=\"{y}\" width=\"{BAR_WIDTH}\" height=\"{bar_height}\" \
+             fill=\"#4575b4\"/>"
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\">{label}</text>",
+            x + BAR_WIDTH / 2.0,
+            bars_top + BAR_HEIGHT + 14.0
+        );
+        let _ = writeln!(
+            svg,
+            "<text x=\"{}\" y=\"{}\" text-anchor=\"middle\" font-size=\"10\">{:.1}%</text>",
+            x + BAR_WIDTH / 2.0,
+            y - 4.0,
+            stats.get_pct(cnt)
+        );
+
+        // The target share is drawn as a dashed line, capped at the top of the chart
+        if let Some(targets) = load_targets
+            && max_finger > 0
+        {
+            let target_cnt = targets[i] / target_total * finger_total as f64;
+            let target_height = (target_cnt / max_finger as f64 * BAR_HEIGHT).min(BAR_HEIGHT);
+            let target_y = bars_top + BAR_HEIGHT - target_height;
+            let _ = writeln!(
+                svg,
+                "<line x1=\"{}\" y1=\"{target_y}\" x2=\"{}\" y2=\"{target_y}\" \
+                 stroke=\"#d73027\" stroke-dasharray=\"3,2\"/>",
+                x - 2.0,
+                x + BAR_WIDTH + 2.0
+            );
+        }
+    }
+
+    // Hand balance
+    // Thumb keys are not counted, so the two sides always add up to 100%
+    let hand_total = stats.left + stats.right;
+    let left_pct = if hand_total > 0 {
+        stats.left as f64 / hand_total as f64 * 100.0
+    } else {
+        0.0
+    };
+    let right_pct = if hand_total > 0 {
+        100.0 - left_pct
+    } else {
+        0.0
+    };
+    let balance_width = kb_width * 0.5;
+    let left_width = balance_width * left_pct / 100.0;
+    let _ = writeln!(
+        svg,
+        "<text x=\"{MARGIN}\" y=\"{}\" font-size=\"14\">Hand balance: left {left_pct:.1}%, \
+         right {right_pct:.1}%</text>",
+        balance_top - 10.0
+    );
+    let _ = writeln!(
+        svg,
+        "<rect x=\"{MARGIN}\" y=\"{balance_top}\" width=\"{left_width}\" height=\"16\" \
+         fill=\"#4575b4\"/>"
+    );
+    let _ = writeln!(
+        svg,
+        "<rect x=\"{}\" y=\"{balance_top}\" width=\"{}\" height=\"16\" fill=\"#d73027\"/>",
+        MARGIN + left_width,
+        balance_width - left_width
+    );
+
+    // Offending bigrams
+    let lists: [(&str, &BTreeMap<(Slot, Slot), usize>); 2] = [
+        ("Top same-finger bigrams", &stats.same_fingers),
+        ("Top scissors", &stats.scissors),
+    ];
+    for (i, (heading, bigrams)) in lists.iter().enumerate() {
+        let x = MARGIN + i as f64 * kb_width / 2.0;
+        let _ = writeln!(
+            svg,
+            "<text x=\"{x}\" y=\"{lists_top}\" font-size=\"14\">{heading}</text>"
+        );
+
+        for (j, line) in get_top_bigrams(kb, stats, bigrams).iter().enumerate() {
+            // Keys in the line are escaped by get_top_bigrams
+            let _ = writeln!(
+                svg,
+                "<text x=\"{x}\" y=\"{}\" font-family=\"monospace\" \
+                 xml:space=\"preserve\">{line}</text>",
+                lists_top + LINE_HEIGHT * (j as f64 + 1.0)
+            );
+        }
+    }
+
+    let _ = writeln!(svg, "</svg>");
+
+    return svg;
+}
+
+fn get_top_bigrams(
+    kb: &Keyboard,
+    stats: &UsageStats,
+    bigrams: &BTreeMap<(Slot, Slot), usize>,
+) -> Vec<String> {
+    let mut sorted: Vec<(&(Slot, Slot), &usize)> = bigrams.iter().collect();
+    sorted.sort_by(|a, b| return b.1.cmp(a.1));
+
+    return sorted
+        .iter()
+        .take(LIST_LEN)
+        .map(|((slot_a, slot_b), cnt)| {
+            let base_a = kb
+                .get_slot_key(*slot_a)
+                .map_or('\0', |k| return k.get_base());
+            let base_b = kb
+                .get_slot_key(*slot_b)
+                .map_or('\0', |k| return k.get_base());
+
+            return format!(
+                "{:>5} {:>5}  {:>8}  {:.3}%",
+                escape_xml(&key_label(base_a)),
+                escape_xml(&key_label(base_b)),
+                cnt,
+                stats.get_pct(**cnt)
+            );
+        })
+        .collect();
+}
+
+// White for unused keys, fading to red for the most used
+fn heat_color(heat: f64) -> String {
+    let clamped = heat.clamp(0.0, 1.0);
+    let red = 255.0 - (255.0 - 215.0) * clamped;
+    let green = 255.0 - (255.0 - 48.0) * clamped;
+    let blue = 255.0 - (255.0 - 39.0) * clamped;
+
+    return format!(
+        "#{:02x}{:02x}{:02x}",
+        red.round() as u8,
+        green.round() as u8,
+        blue.round() as u8
+    );
+}
+
+fn escape_xml(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    for c in input.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+
+    return escaped;
+}
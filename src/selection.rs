@@ -0,0 +1,206 @@
+use core::{cmp, fmt, str::FromStr};
+
+use {
+    anyhow::{Error, anyhow},
+    rand::{Rng as _, rngs::SmallRng, seq::SliceRandom as _},
+};
+
+pub const MIN_TOURNAMENT: usize = 2;
+pub const MAX_TOURNAMENT: usize = 7;
+const DEFAULT_TOURNAMENT: usize = 3;
+
+// How a group of keyboards or populations is narrowed down. Every strategy picks without
+// replacement, so the same member is never chosen twice
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SelectionStrategy {
+    Roulette,
+    Tournament(usize),
+    Rank,
+    Truncation,
+    Sus,
+}
+
+impl SelectionStrategy {
+    pub fn random(rng: &mut SmallRng) -> Self {
+        return match rng.random_range(0..5) {
+            0 => SelectionStrategy::Roulette,
+            1 => SelectionStrategy::Tournament(rng.random_range(MIN_TOURNAMENT..=MAX_TOURNAMENT)),
+            2 => SelectionStrategy::Rank,
+            3 => SelectionStrategy::Truncation,
+            _ => SelectionStrategy::Sus,
+        };
+    }
+
+    // Returns the indexes of up to max_cnt members of fitness. Fitness values are expected to be
+    // non-negative. If every remaining value is zero, picks are made uniformly
+    pub fn select(self, rng: &mut SmallRng, fitness: &[f64], max_cnt: usize) -> Vec<usize> {
+        let cnt = max_cnt.min(fitness.len());
+        let mut remaining: Vec<usize> = (0..fitness.len()).collect();
+        let mut selected: Vec<usize> = Vec::with_capacity(cnt);
+
+        match self {
+            SelectionStrategy::Roulette => {
+                while selected.len() < cnt {
+                    let pos = spin(rng, fitness, &remaining);
+                    selected.push(remaining.swap_remove(pos));
+                }
+            }
+            SelectionStrategy::Tournament(size) => {
+                while selected.len() < cnt {
+                    remaining.shuffle(rng);
+                    let entrants = size.clamp(1, remaining.len());
+                    let pos = (0..entrants)
+                        .max_by(|a, b| return cmp_fitness(fitness, remaining[*a], remaining[*b]))
+                        .expect("Tournament has no entrants");
+                    selected.push(remaining.swap_remove(pos));
+                }
+            }
+            SelectionStrategy::Rank => {
+                // The worst member gets a weight of one, the best a weight of the group size
+                remaining.sort_by(|a, b| return cmp_fitness(fitness, *a, *b));
+                let mut ranks: Vec<f64> = vec![0.0; fitness.len()];
+                for (rank, idx) in remaining.iter().enumerate() {
+                    ranks[*idx] = (rank + 1) as f64;
+                }
+
+                while selected.len() < cnt {
+                    let pos = spin(rng, &ranks, &remaining);
+                    selected.push(remaining.swap_remove(pos));
+                }
+            }
+            SelectionStrategy::Truncation => {
+                remaining.sort_by(|a, b| return cmp_fitness(fitness, *b, *a));
+                selected.extend(remaining.iter().take(cnt));
+            }
+            SelectionStrategy::Sus => {
+                // A member with more fitness than the pointer spacing can land under several
+                // pointers. Duplicates are dropped and the shortfall is filled with another pass
+                while selected.len() < cnt {
+                    let picks = sus_pass(rng, fitness, &remaining, cnt - selected.len());
+                    selected.extend(picks.iter().map(|pos| return remaining[*pos]));
+                    remaining = remaining
+                        .iter()
+                        .enumerate()
+                        .filter(|(pos, _)| return !picks.contains(pos))
+                        .map(|(_, idx)| return *idx)
+                        .collect();
+                }
+            }
+        }
+
+        return selected;
+    }
+}
+
+impl fmt::Display for SelectionStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            SelectionStrategy::Roulette => write!(f, "Roulette"),
+            SelectionStrategy::Tournament(size) => write!(f, "Tournament ({size})"),
+            SelectionStrategy::Rank => write!(f, "Rank"),
+            SelectionStrategy::Truncation => write!(f, "Truncation"),
+            SelectionStrategy::Sus => write!(f, "SUS"),
+        };
+    }
+}
+
+// Tournament size is optional and given after a colon (tournament:5)
+impl FromStr for SelectionStrategy {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let lower = s.to_lowercase();
+        let (name, size_str) = match lower.split_once(':') {
+            Some((before, after)) => (before.trim(), Some(after.trim())),
+            None => (lower.as_str(), None),
+        };
+
+        return match (name, size_str) {
+            ("roulette", None) => Ok(SelectionStrategy::Roulette),
+            ("tournament", None) => Ok(SelectionStrategy::Tournament(DEFAULT_TOURNAMENT)),
+            ("tournament", Some(raw_size)) => match raw_size.parse::<usize>() {
+                Ok(size) if size >= MIN_TOURNAMENT => Ok(SelectionStrategy::Tournament(size)),
+                _ => Err(anyhow!("tournament size must be at least {MIN_TOURNAMENT}")),
+            },
+            ("rank", None) => Ok(SelectionStrategy::Rank),
+            ("truncation", None) => Ok(SelectionStrategy::Truncation),
+            ("sus", None) => Ok(SelectionStrategy::Sus),
+            _ => Err(anyhow!(
+                "expected roulette, tournament[:size], rank, truncation, or sus"
+            )),
+        };
+    }
+}
+
+// Splits items into the selected members, in selection order, and everything else
+pub fn take_selected<T>(items: Vec<T>, selected: &[usize]) -> (Vec<T>, Vec<T>) {
+    let mut slots: Vec<Option<T>> = items.into_iter().map(Some).collect();
+    let chosen: Vec<T> = selected
+        .iter()
+        .map(|i| return slots[*i].take().expect("Index selected twice"))
+        .collect();
+    let rest: Vec<T> = slots.into_iter().flatten().collect();
+
+    return (chosen, rest);
+}
+
+fn cmp_fitness(fitness: &[f64], a: usize, b: usize) -> cmp::Ordering {
+    return fitness[a]
+        .partial_cmp(&fitness[b])
+        .unwrap_or(cmp::Ordering::Equal);
+}
+
+// Returns a position in remaining
+fn spin(rng: &mut SmallRng, weights: &[f64], remaining: &[usize]) -> usize {
+    let total = remaining
+        .iter()
+        .fold(0.0_f64, |acc, i| return acc + weights[*i]);
+    if total <= 0.0 {
+        return rng.random_range(0..remaining.len());
+    }
+
+    let r = rng.random_range(0.0_f64..=total);
+    let mut checked: f64 = 0.0;
+    for (pos, idx) in remaining.iter().enumerate() {
+        checked += weights[*idx];
+        if checked >= r {
+            return pos;
+        }
+    }
+
+    // Floating point error can leave r just past the final sum
+    return remaining.len() - 1;
+}
+
+// Returns distinct positions in remaining, at most cnt of them
+fn sus_pass(rng: &mut SmallRng, fitness: &[f64], remaining: &[usize], cnt: usize) -> Vec<usize> {
+    let total = remaining
+        .iter()
+        .fold(0.0_f64, |acc, i| return acc + fitness[*i]);
+    if total <= 0.0 {
+        return vec![rng.random_range(0..remaining.len())];
+    }
+
+    let spacing = total / cnt as f64;
+    let mut pointer = rng.random_range(0.0_f64..spacing);
+    let mut checked: f64 = 0.0;
+    let mut picks: Vec<usize> = Vec::with_capacity(cnt);
+
+    for (pos, idx) in remaining.iter().enumerate() {
+        checked += fitness[*idx];
+        if checked > pointer {
+            picks.push(pos);
+            while checked > pointer {
+                pointer += spacing;
+            }
+        }
+    }
+
+    if picks.is_empty() {
+        picks.push(remaining.len() - 1);
+    }
+
+    picks.truncate(cnt);
+
+    return picks;
+}
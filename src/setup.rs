@@ -11,32 +11,46 @@ use {
 use anyhow::Result;
 
 use crate::{
+    args::{Command, parse_args},
+    compare::compare_layouts,
+    config::{get_config, initialize_config},
     corpus::initialize_corpus,
-    display::{initial_dsp, update_dvorak, update_iter, update_qwerty},
-    keyboard::Keyboard,
-    meta_pop::MetaPopulation,
+    export::export_layout,
+    layout_file::{load_layout, save_layout},
+    polish::polish,
+    report::write_report,
+    swap_table_file::{format_swap_report, initialize_swap_prior, load_swap_table},
+    sweep::sweep,
+    train::train,
     utils::write_log,
 };
 
-// FUTURE: At some point I'll come up with a way to load key settings from a config rather than
-// having to edit the source code. A lot of things would then need error propagation
-// FUTURE: Keeping the setup naming for now. At some point we're going to add arg processing and then
-// it would make more sense to do that here and then break out actually running the training in its
-// own file
 // FUTURE: Args:
 // - Save file to load
-// - Read from config file
 // - The input options will have restrictions on what is possible. Should be possible to print them
 pub fn setup(log_handle: &mut File, log_dir: &Path) -> Result<ExitCode> {
-    const ITERATIONS: usize = 2000;
-    // const ITERATIONS: usize = 20;
+    let message = "Initializing...";
+    write_log(log_handle, &message)?;
+
+    let args = parse_args()?;
+    initialize_config(args.get_config_path().map(|p| return p.as_path()))?;
+
+    return match args.get_command() {
+        Command::Run => run(log_handle, log_dir),
+        Command::Report { layout, out_path } => report(log_handle, layout, out_path),
+        Command::Compare { layout_a, layout_b } => compare(log_handle, layout_a, layout_b),
+        Command::Polish { layout, out_path } => polish_layout(log_handle, layout, out_path),
+        Command::Sweep => run_sweep(log_handle),
+        Command::SwapReport { swap_table } => swap_report(swap_table),
+        Command::Export { layout, out_path } => export(layout, out_path),
+    };
+}
+
+fn run(log_handle: &mut File, log_dir: &Path) -> Result<ExitCode> {
     const PROG_NAME: &str = "MA Keyboard Generator";
     // SAFETY: PROG_NAME is defined at compile time
     const NAME_DASHES: &str = unsafe { str::from_utf8_unchecked(&[b'='; PROG_NAME.len()]) };
 
-    let message = "Initializing...";
-    write_log(log_handle, &message)?;
-
     println!();
     println!("{NAME_DASHES}");
     println!("{PROG_NAME}");
@@ -49,29 +63,76 @@ pub fn setup(log_handle: &mut File, log_dir: &Path) -> Result<ExitCode> {
         return Ok(exit_code);
     }
 
-    initial_dsp()?;
-    initialize_corpus()?;
+    initialize_corpus(log_handle)?;
+    initialize_swap_prior()?;
+    train(log_handle, log_dir)?;
+
+    return Ok(ExitCode::SUCCESS);
+}
 
-    let mut meta_population = MetaPopulation::create();
+fn report(log_handle: &mut File, layout: &str, out_path: &Path) -> Result<ExitCode> {
+    initialize_corpus(log_handle)?;
 
-    let mut qwerty = Keyboard::create_qwerty();
-    qwerty.eval();
-    update_qwerty(qwerty.get_score())?;
+    let mut kb = load_layout(layout)?;
+    kb.eval();
 
-    let mut dvorak = Keyboard::create_dvorak();
-    dvorak.eval();
-    update_dvorak(dvorak.get_score())?;
+    write_report(&kb, layout, out_path)?;
+    println!("Report written to {}", out_path.display());
 
-    for iter in 1..=ITERATIONS {
-        update_iter(iter)?;
-        meta_population.run_generation()?;
-        meta_population.purge();
-        meta_population.reproduce();
-    }
+    return Ok(ExitCode::SUCCESS);
+}
 
+fn compare(log_handle: &mut File, layout_a: &str, layout_b: &str) -> Result<ExitCode> {
+    initialize_corpus(log_handle)?;
+
+    let mut kb_a = load_layout(layout_a)?;
+    let mut kb_b = load_layout(layout_b)?;
+    compare_layouts(layout_a, &mut kb_a, layout_b, &mut kb_b);
+
+    return Ok(ExitCode::SUCCESS);
+}
+
+fn polish_layout(log_handle: &mut File, layout: &str, out_path: &Path) -> Result<ExitCode> {
+    initialize_corpus(log_handle)?;
+
+    let kb = load_layout(layout)?;
+    let (polished, improvements) = polish(&kb, get_config().polish.cycles);
+
+    let total_gain = improvements
+        .iter()
+        .fold(0.0_f64, |acc, i| return acc + i.get_gain());
     println!();
-    println!("Complete");
-    println!();
+    println!(
+        "{} improvements, {total_gain:+.4} total. Final score: {:.4}",
+        improvements.len(),
+        polished.get_score()
+    );
+
+    save_layout(&polished, out_path)?;
+    println!("Polished layout written to {}", out_path.display());
+
+    return Ok(ExitCode::SUCCESS);
+}
+
+fn run_sweep(log_handle: &mut File) -> Result<ExitCode> {
+    initialize_corpus(log_handle)?;
+    initialize_swap_prior()?;
+    sweep(log_handle)?;
+
+    return Ok(ExitCode::SUCCESS);
+}
+
+fn swap_report(path: &Path) -> Result<ExitCode> {
+    let swap_table = load_swap_table(path)?;
+    print!("{}", format_swap_report(&swap_table));
+
+    return Ok(ExitCode::SUCCESS);
+}
+
+fn export(layout: &str, out_path: &Path) -> Result<ExitCode> {
+    let kb = load_layout(layout)?;
+    export_layout(&kb, out_path)?;
+    println!("Keymap written to {}", out_path.display());
 
     return Ok(ExitCode::SUCCESS);
 }
@@ -0,0 +1,110 @@
+use core::fmt;
+use std::time::{Duration, Instant};
+
+use crate::{config::StopConfig, keyboard::get_eval_cnt, meta_pop::MetaPopulation};
+
+#[derive(Debug, Clone, Copy)]
+pub enum StopReason {
+    MaxGenerations(usize),
+    TimeLimit(Duration),
+    MaxEvals(usize),
+    TargetScore(f64),
+    Stagnation(usize),
+    LowDiversity(f64),
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            StopReason::MaxGenerations(generation) => {
+                write!(f, "Reached the generation limit ({generation})")
+            }
+            StopReason::TimeLimit(elapsed) => {
+                write!(
+                    f,
+                    "Reached the time limit ({} seconds elapsed)",
+                    elapsed.as_secs()
+                )
+            }
+            StopReason::MaxEvals(evals) => write!(f, "Reached the evaluation limit ({evals})"),
+            StopReason::TargetScore(score) => write!(f, "Reached the target score ({score})"),
+            StopReason::Stagnation(gens) => {
+                write!(f, "No improvement in the top score for {gens} generations")
+            }
+            StopReason::LowDiversity(diversity) => {
+                write!(f, "Diversity between populations fell to {diversity:.03}")
+            }
+        };
+    }
+}
+
+// NOTE: Criteria are checked once per meta-generation, so the time and evaluation limits can
+// overshoot by up to one generation's worth of work
+pub struct StopTracker {
+    criteria: StopConfig,
+    start: Instant,
+    best_score: f64,
+    last_improvement: usize,
+}
+
+impl StopTracker {
+    pub fn new(criteria: StopConfig) -> Self {
+        return Self {
+            criteria,
+            start: Instant::now(),
+            best_score: 0.0,
+            last_improvement: 0,
+        };
+    }
+
+    pub fn check(&mut self, meta_population: &MetaPopulation) -> Option<StopReason> {
+        let generation = meta_population.get_generation();
+        let top_score = meta_population.get_top_score();
+        if top_score > self.best_score {
+            self.best_score = top_score;
+            self.last_improvement = generation;
+        }
+
+        if let Some(target) = self.criteria.target_score
+            && top_score >= target
+        {
+            return Some(StopReason::TargetScore(top_score));
+        }
+
+        if let Some(max_generations) = self.criteria.max_generations
+            && generation >= max_generations
+        {
+            return Some(StopReason::MaxGenerations(generation));
+        }
+
+        let elapsed = self.start.elapsed();
+        if let Some(max_time) = self.criteria.max_time
+            && elapsed >= max_time
+        {
+            return Some(StopReason::TimeLimit(elapsed));
+        }
+
+        let evals = get_eval_cnt();
+        if let Some(max_evals) = self.criteria.max_evals
+            && evals >= max_evals
+        {
+            return Some(StopReason::MaxEvals(evals));
+        }
+
+        let stale_gens = generation - self.last_improvement;
+        if let Some(stagnation_gens) = self.criteria.stagnation_gens
+            && stale_gens >= stagnation_gens
+        {
+            return Some(StopReason::Stagnation(stale_gens));
+        }
+
+        if let Some(min_diversity) = self.criteria.min_diversity {
+            let diversity = meta_population.get_diversity().get_mean();
+            if diversity < min_diversity {
+                return Some(StopReason::LowDiversity(diversity));
+            }
+        }
+
+        return None;
+    }
+}
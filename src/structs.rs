@@ -1,8 +1,11 @@
 use crate::{
     kb_builders::check_col,
-    keyboard::{ASCII_CNT, BOT_ROW, NUM_ROW},
+    keyboard::{LAYER_ROW, MAX_LAYERS, NUM_ROW, TOP_ROW},
 };
 
+// Physical rows per layer: top, home, and bottom
+const LAYER_ROW_CNT: usize = 3;
+
 pub struct IdSpawner {
     next_id: usize,
 }
@@ -31,11 +34,10 @@ impl Slot {
     // PERF: If this is used in a hot loop, change to debug_assert
     pub fn from_tuple(source: (usize, usize)) -> Self {
         assert!(
-            (NUM_ROW..=BOT_ROW).contains(&source.0),
-            "Source row ({}) < num_row ({}) or > bottom row ({}) in slot.from_tuple",
-            NUM_ROW,
-            BOT_ROW,
+            (NUM_ROW..LAYER_ROW + MAX_LAYERS * LAYER_ROW_CNT).contains(&source.0),
+            "Source row ({}) < num_row ({}) or past the last layer row in slot.from_tuple",
             source.0,
+            NUM_ROW,
         );
 
         assert!(
@@ -58,40 +60,65 @@ impl Slot {
     pub fn get_col(&self) -> usize {
         return self.col;
     }
+
+    // The same key position on a layer. Layers are numbered from 1, and phys_row is the top, home,
+    // or bottom row
+    pub fn from_layer(layer: usize, phys_row: usize, col: usize) -> Self {
+        debug_assert!(layer > 0, "Layer 0 is the base layer in Slot::from_layer");
+
+        return Self::from_tuple((
+            LAYER_ROW + (layer - 1) * LAYER_ROW_CNT + (phys_row - TOP_ROW),
+            col,
+        ));
+    }
+
+    // 0 for the base layer and the thumb keys
+    pub fn get_layer(&self) -> usize {
+        if self.row < LAYER_ROW {
+            return 0;
+        }
+
+        return (self.row - LAYER_ROW) / LAYER_ROW_CNT + 1;
+    }
+
+    // Where the key sits under the fingers. Layer slots share the alpha area's positions
+    pub fn get_phys(self) -> Self {
+        if self.row < LAYER_ROW {
+            return self;
+        }
+
+        return Self {
+            row: TOP_ROW + (self.row - LAYER_ROW) % LAYER_ROW_CNT,
+            col: self.col,
+        };
+    }
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Key {
-    base: u8,
-    shift: u8,
+    base: char,
+    shift: char,
 }
 
 impl Key {
-    // PERF: If this is run in a hot loop, change to debug_assert
-    pub fn from_tuple(source: (u8, u8)) -> Self {
-        assert!(
-            usize::from(source.0) <= ASCII_CNT,
-            "Base key {} is not a valid ASCII char in Key::from_tuple",
-            source.0
-        );
-
-        assert!(
-            usize::from(source.1) <= ASCII_CNT,
-            "Shift key {} is not a valid ASCII char in Key::from_tuple",
-            source.0
-        );
+    // Fills the empty slots on a layer. NUL never appears in a corpus
+    pub const BLANK: Self = Self {
+        base: '\0',
+        shift: '\0',
+    };
 
+    pub fn from_tuple(source: (char, char)) -> Self {
         return Self {
             base: source.0,
             shift: source.1,
         };
     }
 
-    pub fn get_base(self) -> u8 {
+    pub fn get_base(self) -> char {
         return self.base;
     }
 
-    pub fn get_shift(self) -> u8 {
+    pub fn get_shift(self) -> char {
         return self.shift;
     }
 }
@@ -0,0 +1,193 @@
+use {
+    core::{
+        cmp,
+        fmt::{self, Write as _},
+        str::FromStr,
+    },
+    std::{fs, path::Path, sync::OnceLock},
+};
+
+use anyhow::{Result, anyhow};
+
+use crate::{
+    config::get_config,
+    kb_builders::get_swappable_keys,
+    keys,
+    layout_file::{escape_key, unescape_key},
+    population::{SwapScore, SwapTable},
+    structs::{Key, Slot},
+    swappable_keys,
+};
+
+swappable_keys!();
+
+const HEADER: &str = "# ma_keyboard swap table";
+// Entries shown in each section of a swap report
+const REPORT_CNT: usize = 20;
+
+// Loaded once from swap_table.load. Every new population starts from a copy
+pub static SWAP_PRIOR: OnceLock<SwapTable> = OnceLock::new();
+
+pub fn initialize_swap_prior() -> Result<()> {
+    let config = &get_config().swap_table;
+    let Some(path) = &config.load else {
+        return Ok(());
+    };
+
+    let mut swap_table = load_swap_table(path)?;
+    swap_table.decay_weights(config.load_decay);
+
+    if SWAP_PRIOR.set(swap_table).is_err() {
+        return Err(anyhow!("Failed to initialize SWAP_PRIOR"));
+    }
+
+    return Ok(());
+}
+
+pub fn get_swap_prior() -> Option<&'static SwapTable> {
+    return SWAP_PRIOR.get();
+}
+
+// One entry per line: row, column, key, weighted average, and accumulated weight. Keys are
+// escaped the same way as in layout files
+pub fn save_swap_table(swap_table: &SwapTable, path: &Path) -> Result<()> {
+    let mut contents = String::new();
+    let _ = writeln!(contents, "{HEADER}");
+
+    for (slot, key, score) in swap_table.get_entries() {
+        let _ = writeln!(
+            contents,
+            "{} {} {} {} {}",
+            slot.get_row(),
+            slot.get_col(),
+            escape_key(key.get_base()),
+            score.get_w_avg(),
+            score.get_weights()
+        );
+    }
+
+    fs::write(path, contents)?;
+
+    return Ok(());
+}
+
+// Entries missing from the file keep their defaults
+pub fn load_swap_table(path: &Path) -> Result<SwapTable> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) => {
+            let err_string = format!("Unable to open swap table {} -- {}", path.display(), e);
+            return Err(anyhow!(err_string));
+        }
+    };
+
+    let mut swap_table = SwapTable::new();
+    let lines = contents
+        .lines()
+        .map(str::trim)
+        .filter(|l| return !l.is_empty() && !l.starts_with('#'));
+
+    for line in lines {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let [row_str, col_str, key_str, w_avg_str, weights_str] = fields.as_slice() else {
+            return Err(anyhow!("Invalid line \"{line}\" in {}", path.display()));
+        };
+
+        let row: usize = parse_field(row_str, line, path)?;
+        let col: usize = parse_field(col_str, line, path)?;
+        let w_avg: f64 = parse_field(w_avg_str, line, path)?;
+        let raw_weights: f64 = parse_field(weights_str, line, path)?;
+
+        let base = unescape_key(key_str)?;
+        let Some(key) = get_swappable_keys(&SWAPPABLE_KEYS)
+            .into_iter()
+            .find(|k| return k.get_base() == base)
+        else {
+            return Err(anyhow!(
+                "Key \"{key_str}\" in {} is not swappable",
+                path.display()
+            ));
+        };
+
+        if row > 3 || col > 9 || !w_avg.is_finite() || !raw_weights.is_finite() {
+            return Err(anyhow!(
+                "Out of range value in \"{line}\" in {}",
+                path.display()
+            ));
+        }
+
+        // A weight is an accumulated count, so a negative one carries no confidence. Treat it as
+        // unobserved rather than rejecting the whole file
+        let weights = raw_weights.max(0.0_f64);
+
+        swap_table.replace_score(row, col, key, SwapScore::from_values(w_avg, weights));
+    }
+
+    return Ok(swap_table);
+}
+
+fn parse_field<T: FromStr>(field: &str, line: &str, path: &Path) -> Result<T>
+where
+    T::Err: fmt::Display,
+{
+    return field.parse::<T>().map_err(|e| {
+        return anyhow!(
+            "Invalid number \"{field}\" in \"{line}\" in {} -- {e}",
+            path.display()
+        );
+    });
+}
+
+// Entries are recorded when a key leaves a slot, so a high average means the key tends to do
+// better elsewhere. A low average means moving it out usually hurt. Entries that were never
+// observed are left out
+pub fn format_swap_report(swap_table: &SwapTable) -> String {
+    let mut observed: Vec<(Slot, Key, SwapScore)> = swap_table
+        .get_entries()
+        .into_iter()
+        .filter(|(_, _, score)| return score.get_weights() > 0.0)
+        .collect();
+
+    observed.sort_by(|a, b| {
+        return b
+            .2
+            .get_w_avg()
+            .partial_cmp(&a.2.get_w_avg())
+            .unwrap_or(cmp::Ordering::Equal);
+    });
+
+    let mut report = String::new();
+    let _ = writeln!(
+        report,
+        "{} of {} entries observed",
+        observed.len(),
+        swap_table.get_entries().len()
+    );
+    let _ = writeln!(report);
+
+    let _ = writeln!(report, "-- Keys that want to leave their slot --");
+    for entry in observed.iter().take(REPORT_CNT) {
+        let _ = writeln!(report, "{}", format_entry(entry));
+    }
+
+    let _ = writeln!(report);
+    let _ = writeln!(report, "-- Keys that want to stay in their slot --");
+    for entry in observed.iter().rev().take(REPORT_CNT) {
+        let _ = writeln!(report, "{}", format_entry(entry));
+    }
+
+    return report;
+}
+
+fn format_entry(entry: &(Slot, Key, SwapScore)) -> String {
+    let (slot, key, score) = entry;
+
+    return format!(
+        "{:<4} Row {}, Col {}  Avg. Change: {:+14.4}  Weight: {:8.3}",
+        format!("'{}'", escape_key(key.get_base())),
+        slot.get_row(),
+        slot.get_col(),
+        score.get_w_avg(),
+        score.get_weights()
+    );
+}
@@ -0,0 +1,311 @@
+use core::{cmp, fmt, iter, str::FromStr};
+use std::{
+    fs::File,
+    time::{Duration, Instant},
+};
+
+use {
+    anyhow::{Error, Result, anyhow},
+    rand::{Rng as _, SeedableRng as _, rngs::SmallRng},
+};
+
+use crate::{
+    config::{PopulationConfig, SweepConfig, get_config},
+    display::set_quiet,
+    population::{PopParams, Population},
+    utils::write_log,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SweepMode {
+    Grid,
+    Random,
+}
+
+impl fmt::Display for SweepMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            SweepMode::Grid => write!(f, "Grid"),
+            SweepMode::Random => write!(f, "Random"),
+        };
+    }
+}
+
+impl FromStr for SweepMode {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "grid" => Ok(SweepMode::Grid),
+            "random" => Ok(SweepMode::Random),
+            _ => Err(anyhow!("expected grid or random")),
+        };
+    }
+}
+
+struct RunResult {
+    best_score: f64,
+    time_to_target: Option<Duration>,
+}
+
+struct SweepResult {
+    params: PopParams,
+    runs: Vec<RunResult>,
+}
+
+impl SweepResult {
+    fn get_mean(&self) -> f64 {
+        let total = self
+            .runs
+            .iter()
+            .fold(0.0_f64, |acc, r| return acc + r.best_score);
+
+        return total / self.runs.len() as f64;
+    }
+
+    // Population standard deviation. With the default three repeats this is a rough signal, not
+    // an estimate to lean on
+    fn get_std_dev(&self) -> f64 {
+        let mean = self.get_mean();
+        let total = self
+            .runs
+            .iter()
+            .fold(0.0_f64, |acc, r| return acc + (r.best_score - mean).powi(2));
+
+        return (total / self.runs.len() as f64).sqrt();
+    }
+
+    fn get_best(&self) -> f64 {
+        return self
+            .runs
+            .iter()
+            .fold(f64::MIN, |acc, r| return acc.max(r.best_score));
+    }
+
+    fn get_hits(&self) -> usize {
+        return self
+            .runs
+            .iter()
+            .filter(|r| return r.time_to_target.is_some())
+            .count();
+    }
+
+    // Averaged over the runs that reached the target only
+    fn get_mean_time_to_target(&self) -> Option<Duration> {
+        let times: Vec<Duration> = self
+            .runs
+            .iter()
+            .filter_map(|r| return r.time_to_target)
+            .collect();
+        if times.is_empty() {
+            return None;
+        }
+
+        let total: Duration = times.iter().sum();
+        return Some(total / times.len() as u32);
+    }
+
+    fn get_row(&self, has_target: bool) -> String {
+        let target_cols = if has_target {
+            let time = self.get_mean_time_to_target().map_or("--".to_owned(), |t| {
+                return format!("{:.1}", t.as_secs_f64());
+            });
+            format!(
+                " | {:>4}/{:<4} | {:>9}",
+                self.get_hits(),
+                self.runs.len(),
+                time
+            )
+        } else {
+            String::new()
+        };
+
+        return format!(
+            "{:>10.4} | {:>6.4} | {:>8} | {:>8.3} | {:>14.4} | {:>14.4} | {:>12.4}{}",
+            self.params.k_temp,
+            self.params.score_decay,
+            self.params.mutation,
+            self.params.climb_pct,
+            self.get_mean(),
+            self.get_best(),
+            self.get_std_dev(),
+            target_cols
+        );
+    }
+}
+
+// Runs a single population for a fixed number of generations per parameter set, repeated with
+// fresh seeds, then prints a table sorted by mean best score. The live display is turned off so
+// the table and progress lines stay readable
+// NOTE: Each run is a lone population with no meta-evolution, migration, or purging, so scores
+// are lower than a full run would reach. The table is for comparing settings against each other
+pub fn sweep(log_handle: &mut File) -> Result<()> {
+    set_quiet();
+
+    let config = &get_config().sweep;
+    let bounds = &get_config().population;
+
+    let seed: [u8; 32] = rand::random();
+    let mut rng = SmallRng::from_seed(seed);
+
+    let param_sets = match config.mode {
+        SweepMode::Grid => get_grid(bounds, config.grid_steps),
+        SweepMode::Random => get_samples(&mut rng, bounds, config.samples),
+    };
+
+    let message = format!(
+        "{} sweep: {} parameter sets, {} runs each, {} generations per run",
+        config.mode,
+        param_sets.len(),
+        config.repeats,
+        config.generations
+    );
+    println!("{message}");
+    println!();
+    write_log(log_handle, &message)?;
+
+    let set_cnt = param_sets.len();
+    let mut results: Vec<SweepResult> = Vec::with_capacity(set_cnt);
+    for (i, params) in param_sets.into_iter().enumerate() {
+        let mut runs: Vec<RunResult> = Vec::with_capacity(config.repeats);
+        for _ in 0..config.repeats {
+            runs.push(run_once(params, config)?);
+        }
+
+        let result = SweepResult { params, runs };
+        println!(
+            "[{:03}/{:03}] K Temp: {:.4}, Decay: {:.4}, Mutation: {}, Climb Pct: {:.3} -- Mean \
+             Best: {:.4}",
+            i + 1,
+            set_cnt,
+            params.k_temp,
+            params.score_decay,
+            params.mutation,
+            params.climb_pct,
+            result.get_mean()
+        );
+        results.push(result);
+    }
+
+    results.sort_by(|a, b| {
+        return b
+            .get_mean()
+            .partial_cmp(&a.get_mean())
+            .unwrap_or(cmp::Ordering::Equal);
+    });
+
+    let has_target = config.target_score.is_some();
+    let target_header = if has_target {
+        format!(" | {:^9} | {:>9}", "Hits", "Time (s)")
+    } else {
+        String::new()
+    };
+    let header = format!(
+        "{:>10} | {:>6} | {:>8} | {:>8} | {:>14} | {:>14} | {:>12}{}",
+        "K Temp", "Decay", "Mutation", "Climb", "Mean Best", "Best", "Std Dev", target_header
+    );
+
+    println!();
+    println!("{header}");
+    println!("{}", "-".repeat(header.len()));
+    write_log(log_handle, &header)?;
+    for result in &results {
+        let row = result.get_row(has_target);
+        println!("{row}");
+        write_log(log_handle, &row)?;
+    }
+
+    return Ok(());
+}
+
+fn run_once(params: PopParams, config: &SweepConfig) -> Result<RunResult> {
+    let seed: [u8; 32] = rand::random();
+    let mut population = Population::with_params(0, SmallRng::from_seed(seed), params);
+
+    let start = Instant::now();
+    let mut time_to_target: Option<Duration> = None;
+    for generation in 1..=config.generations {
+        population.refill_pop();
+        population.eval_gen_pop()?;
+        population.filter_climbers();
+        population.climb_kbs(generation)?;
+
+        if time_to_target.is_none()
+            && let Some(target) = config.target_score
+            && population.get_top_score() >= target
+        {
+            time_to_target = Some(start.elapsed());
+        }
+    }
+
+    return Ok(RunResult {
+        best_score: population.get_top_score(),
+        time_to_target,
+    });
+}
+
+fn get_pop_cnt(bounds: &PopulationConfig) -> usize {
+    return usize::midpoint(bounds.min_pop, bounds.max_pop);
+}
+
+// Every combination of evenly spaced values for each parameter. Parameters with equal bounds
+// contribute a single value
+fn get_grid(bounds: &PopulationConfig, steps: usize) -> Vec<PopParams> {
+    let k_temps = get_steps(bounds.min_k_temp, bounds.max_k_temp, steps);
+    let decays = get_steps(bounds.min_score_decay, bounds.max_score_decay, steps);
+    let climb_pcts = get_steps(bounds.min_climb_pct, bounds.max_climb_pct, steps);
+
+    let mut mutations: Vec<usize> = get_steps(
+        bounds.min_mutation as f64,
+        bounds.max_mutation as f64,
+        steps,
+    )
+    .into_iter()
+    .map(|m| return m.round() as usize)
+    .collect();
+    mutations.dedup();
+
+    let mut grid: Vec<PopParams> = Vec::new();
+    for k_temp in &k_temps {
+        for score_decay in &decays {
+            for mutation in &mutations {
+                for climb_pct in &climb_pcts {
+                    grid.push(PopParams {
+                        pop_cnt: get_pop_cnt(bounds),
+                        climb_pct: *climb_pct,
+                        mutation: *mutation,
+                        k_temp: *k_temp,
+                        score_decay: *score_decay,
+                    });
+                }
+            }
+        }
+    }
+
+    return grid;
+}
+
+fn get_steps(min: f64, max: f64, steps: usize) -> Vec<f64> {
+    if steps == 1 || min >= max {
+        return vec![min + (max - min) / 2.0];
+    }
+
+    let step_size = (max - min) / (steps - 1) as f64;
+    return (0..steps)
+        .map(|i| return min + step_size * i as f64)
+        .collect();
+}
+
+fn get_samples(rng: &mut SmallRng, bounds: &PopulationConfig, cnt: usize) -> Vec<PopParams> {
+    return iter::repeat_with(|| {
+        return PopParams {
+            pop_cnt: get_pop_cnt(bounds),
+            climb_pct: rng.random_range(bounds.min_climb_pct..=bounds.max_climb_pct),
+            mutation: rng.random_range(bounds.min_mutation..=bounds.max_mutation),
+            k_temp: rng.random_range(bounds.min_k_temp..=bounds.max_k_temp),
+            score_decay: rng.random_range(bounds.min_score_decay..=bounds.max_score_decay),
+        };
+    })
+    .take(cnt)
+    .collect();
+}
@@ -0,0 +1,86 @@
+use std::{fs::File, path::Path};
+
+use anyhow::Result;
+
+use crate::{
+    config::get_config,
+    display::{initial_dsp, update_dvorak, update_iter, update_qwerty},
+    keyboard::Keyboard,
+    layout_file::save_layout,
+    meta_pop::MetaPopulation,
+    polish::polish,
+    report::write_report,
+    stopping::{StopReason, StopTracker},
+    swap_table_file::save_swap_table,
+    utils::write_log,
+};
+
+pub fn train(log_handle: &mut File, log_dir: &Path) -> Result<()> {
+    initial_dsp()?;
+
+    let mut meta_population = MetaPopulation::create();
+
+    let mut qwerty = Keyboard::create_qwerty();
+    qwerty.eval();
+    update_qwerty(qwerty.get_score())?;
+
+    let mut dvorak = Keyboard::create_dvorak();
+    dvorak.eval();
+    update_dvorak(dvorak.get_score())?;
+
+    let mut stop_tracker = StopTracker::new(get_config().stop.clone());
+    let stop_reason: StopReason = loop {
+        update_iter(meta_population.get_generation() + 1)?;
+        meta_population.run_generation()?;
+
+        if let Some(reason) = stop_tracker.check(&meta_population) {
+            break reason;
+        }
+
+        if get_config().meta.evolve {
+            meta_population.purge();
+            meta_population.reproduce();
+        }
+    };
+
+    let stop_message = format!("Stopped: {stop_reason}");
+    write_log(log_handle, &stop_message)?;
+
+    let mut best_kb = meta_population.get_best_kb().kb_clone();
+    if get_config().polish.after_run {
+        println!();
+        println!("Polishing best layout...");
+
+        let (polished, improvements) = polish(&best_kb, get_config().polish.cycles);
+        for improvement in &improvements {
+            write_log(log_handle, &format!("Polish {improvement}"))?;
+        }
+
+        best_kb = polished;
+    }
+
+    let layout_path = log_dir.join("best_layout.txt");
+    save_layout(&best_kb, &layout_path)?;
+    let report_path = log_dir.join("best_layout.html");
+    write_report(&best_kb, "Best Layout", &report_path)?;
+
+    let swap_table_path = log_dir.join("best_swap_table.txt");
+    if get_config().swap_table.save {
+        save_swap_table(
+            meta_population.get_best_pop().get_swap_table(),
+            &swap_table_path,
+        )?;
+    }
+
+    println!();
+    println!("Complete");
+    println!("{stop_message}");
+    println!("Best layout: {}", layout_path.display());
+    println!("Best layout report: {}", report_path.display());
+    if get_config().swap_table.save {
+        println!("Best swap table: {}", swap_table_path.display());
+    }
+    println!();
+
+    return Ok(());
+}
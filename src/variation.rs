@@ -0,0 +1,272 @@
+use core::{f64::consts::PI, fmt, str::FromStr};
+
+use {
+    anyhow::{Error, anyhow},
+    rand::{Rng as _, rngs::SmallRng},
+};
+
+use crate::config::{PopulationConfig, get_config};
+
+// Step sizes are measured as a share of the gene's range
+const MIN_SIGMA: f64 = 0.001;
+const MAX_SIGMA: f64 = 0.5;
+// Learning rate for the step sizes. 1 / sqrt(n) for the five hyperparameters. Population size and
+// mutation are integers, but are stepped on a continuous scale and rounded
+const TAU: f64 = 0.447_213_595;
+
+// How a child population combines its parents' hyperparameters
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Recombination {
+    // Take one parent's value, favouring the higher scoring parent
+    Pick,
+    // Blend crossover. Uniform over the parents' interval, widened by alpha on each side
+    Blx,
+    // Simulated binary crossover. Children land near the parents, spread controlled by eta
+    Sbx,
+}
+
+impl fmt::Display for Recombination {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Recombination::Pick => write!(f, "Pick"),
+            Recombination::Blx => write!(f, "BLX"),
+            Recombination::Sbx => write!(f, "SBX"),
+        };
+    }
+}
+
+impl FromStr for Recombination {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "pick" => Ok(Recombination::Pick),
+            "blx" => Ok(Recombination::Blx),
+            "sbx" => Ok(Recombination::Sbx),
+            _ => Err(anyhow!("expected pick, blx, or sbx")),
+        };
+    }
+}
+
+// How the recombined value is mutated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Perturbation {
+    // With probability population.mutation_rate, replace the value with a uniform draw
+    Resample,
+    // Add Gaussian noise with a step size that is itself inherited and mutated
+    Gaussian,
+}
+
+impl fmt::Display for Perturbation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        return match self {
+            Perturbation::Resample => write!(f, "Resample"),
+            Perturbation::Gaussian => write!(f, "Gaussian"),
+        };
+    }
+}
+
+impl FromStr for Perturbation {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        return match s.to_lowercase().as_str() {
+            "resample" => Ok(Perturbation::Resample),
+            "gaussian" => Ok(Perturbation::Gaussian),
+            _ => Err(anyhow!("expected resample or gaussian")),
+        };
+    }
+}
+
+// The bounds of one hyperparameter. Variation happens on the unit interval so step sizes mean
+// the same thing for every gene. Integer genes get half a step of padding on each end so that
+// rounding gives every value an equal share of the interval
+#[derive(Debug, Clone, Copy)]
+pub struct Gene {
+    min: f64,
+    max: f64,
+    log: bool,
+}
+
+impl Gene {
+    pub fn pop_cnt(bounds: &PopulationConfig) -> Self {
+        return Self::integer(bounds.min_pop, bounds.max_pop);
+    }
+
+    pub fn climb_pct(bounds: &PopulationConfig) -> Self {
+        return Self::linear(bounds.min_climb_pct, bounds.max_climb_pct);
+    }
+
+    pub fn mutation(bounds: &PopulationConfig) -> Self {
+        return Self::integer(bounds.min_mutation, bounds.max_mutation);
+    }
+
+    // k_temp acts through an exponent, so its useful values can span orders of magnitude.
+    // variation.log_k_temp varies it in log space to keep steps proportional. Config validation
+    // makes sure the bounds then share a sign
+    pub fn k_temp(bounds: &PopulationConfig) -> Self {
+        let mut gene = Self::linear(bounds.min_k_temp, bounds.max_k_temp);
+        gene.log = get_config().variation.log_k_temp;
+
+        return gene;
+    }
+
+    pub fn score_decay(bounds: &PopulationConfig) -> Self {
+        return Self::linear(bounds.min_score_decay, bounds.max_score_decay);
+    }
+
+    fn linear(min: f64, max: f64) -> Self {
+        return Self {
+            min,
+            max,
+            log: false,
+        };
+    }
+
+    fn integer(min: usize, max: usize) -> Self {
+        return Self::linear(min as f64 - 0.5_f64, max as f64 + 0.5_f64);
+    }
+
+    fn value_to_unit(self, value: f64) -> f64 {
+        if self.log {
+            let lo = self.min.abs().ln();
+            let hi = self.max.abs().ln();
+            return (value.abs().ln() - lo) / (hi - lo);
+        }
+
+        return (value - self.min) / (self.max - self.min);
+    }
+
+    fn unit_to_value(self, unit: f64) -> f64 {
+        if self.log {
+            let lo = self.min.abs().ln();
+            let hi = self.max.abs().ln();
+            return self.min.signum() * (lo + unit * (hi - lo)).exp();
+        }
+
+        return self.min + unit * (self.max - self.min);
+    }
+}
+
+// Per-gene step sizes for Gaussian perturbation. Each population carries its own, so settings
+// that keep producing good children also keep the step sizes that found them
+#[derive(Debug, Clone, Copy)]
+pub struct StepSizes {
+    pub pop_cnt: f64,
+    pub climb_pct: f64,
+    pub mutation: f64,
+    pub k_temp: f64,
+    pub score_decay: f64,
+}
+
+impl StepSizes {
+    pub fn new() -> Self {
+        let sigma = get_config().variation.initial_sigma;
+
+        return Self {
+            pop_cnt: sigma,
+            climb_pct: sigma,
+            mutation: sigma,
+            k_temp: sigma,
+            score_decay: sigma,
+        };
+    }
+
+    pub fn get_mean(&self) -> f64 {
+        return (self.pop_cnt + self.climb_pct + self.mutation + self.k_temp + self.score_decay)
+            / 5.0_f64;
+    }
+}
+
+// Returns the child's value and step size. Values from either parent may be outside the current
+// bounds if the config changed, so they are clamped before use
+pub fn vary(
+    rng: &mut SmallRng,
+    gene: Gene,
+    values: (f64, f64),
+    sigmas: (f64, f64),
+    top_a_pct: f64,
+) -> (f64, f64) {
+    let config = &get_config().variation;
+    let mut sigma = (sigmas.0 * sigmas.1).sqrt();
+
+    if gene.max <= gene.min {
+        return (gene.min, sigma);
+    }
+
+    let unit_a = gene.value_to_unit(values.0).clamp(0.0_f64, 1.0_f64);
+    let unit_b = gene.value_to_unit(values.1).clamp(0.0_f64, 1.0_f64);
+
+    let mut child = match config.recombination {
+        Recombination::Pick => {
+            if rng.random_range(0.0_f64..=1.0_f64) <= top_a_pct {
+                unit_a
+            } else {
+                unit_b
+            }
+        }
+        Recombination::Blx => blx(rng, unit_a, unit_b, config.blx_alpha),
+        Recombination::Sbx => sbx(rng, unit_a, unit_b, config.sbx_eta, top_a_pct),
+    };
+
+    match config.perturbation {
+        Perturbation::Resample => {
+            if rng.random_range(0.0_f64..=1.0_f64) <= get_config().population.mutation_rate {
+                child = rng.random_range(0.0_f64..=1.0_f64);
+            }
+        }
+        Perturbation::Gaussian => {
+            sigma = (sigma * (TAU * sample_normal(rng)).exp()).clamp(MIN_SIGMA, MAX_SIGMA);
+            child += sigma * sample_normal(rng);
+        }
+    }
+
+    return (gene.unit_to_value(reflect(child)), sigma);
+}
+
+// Standard normal draw using the Box-Muller transform
+pub fn sample_normal(rng: &mut SmallRng) -> f64 {
+    // The lower bound keeps ln away from zero
+    let u1: f64 = rng.random_range(f64::EPSILON..1.0_f64);
+    let u2: f64 = rng.random_range(0.0_f64..1.0_f64);
+
+    return (-2.0_f64 * u1.ln()).sqrt() * (2.0_f64 * PI * u2).cos();
+}
+
+fn blx(rng: &mut SmallRng, a: f64, b: f64, alpha: f64) -> f64 {
+    let lo = a.min(b);
+    let hi = a.max(b);
+    let spread = (hi - lo) * alpha;
+
+    return rng.random_range((lo - spread)..=(hi + spread));
+}
+
+// Of the two children SBX produces, the one nearer the higher scoring parent is more likely
+fn sbx(rng: &mut SmallRng, a: f64, b: f64, eta: f64, top_a_pct: f64) -> f64 {
+    let u: f64 = rng.random_range(0.0_f64..1.0_f64);
+    let beta = if u <= 0.5_f64 {
+        (2.0_f64 * u).powf(1.0_f64 / (eta + 1.0_f64))
+    } else {
+        (1.0_f64 / (2.0_f64 * (1.0_f64 - u))).powf(1.0_f64 / (eta + 1.0_f64))
+    };
+
+    let near_a = 0.5_f64 * ((1.0_f64 + beta) * a + (1.0_f64 - beta) * b);
+    let near_b = 0.5_f64 * ((1.0_f64 - beta) * a + (1.0_f64 + beta) * b);
+
+    return if rng.random_range(0.0_f64..=1.0_f64) <= top_a_pct {
+        near_a
+    } else {
+        near_b
+    };
+}
+
+// Bounces values that step past either end back inside, so the edges are not over-sampled the
+// way clamping would
+fn reflect(unit: f64) -> f64 {
+    let mut reflected = unit.rem_euclid(2.0_f64);
+    if reflected > 1.0_f64 {
+        reflected = 2.0_f64 - reflected;
+    }
+
+    return reflected;
+}